@@ -1,3 +1,4 @@
+pub mod utils;
 pub mod years;
 
 pub fn init() -> AdventManager {
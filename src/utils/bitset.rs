@@ -0,0 +1,53 @@
+/// A fixed size set of small integers, one bit each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    // a set that can hold the values `0..len`
+    pub fn new(len: usize) -> BitSet {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    // adds a value, returning whether it wasn't in the set yet
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(value < self.len, "{} is outside of the set", value);
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.len && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_contains() {
+        let mut set = BitSet::new(130);
+
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+
+        assert!(set.contains(129));
+        assert!(!set.contains(63));
+        assert!(!set.contains(130));
+        assert_eq!(3, set.count());
+    }
+}
@@ -0,0 +1,140 @@
+/// A point on the integer lattice
+pub type Point = (i64, i64);
+
+/// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple polygon with lattice point vertices
+///
+/// vertices are in order around the polygon, the last one connects back to
+/// the first, either winding direction works
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    // every edge as a pair of vertices, including the closing edge
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Twice the signed area, using the shoelace formula
+    ///
+    /// positive when the vertices wind anticlockwise with y pointing up,
+    /// doubled so the result is always an exact integer
+    pub fn signed_double_area(&self) -> i64 {
+        self.edges().map(|(a, b)| a.0 * b.1 - b.0 * a.1).sum()
+    }
+
+    pub fn double_area(&self) -> u64 {
+        self.signed_double_area().unsigned_abs()
+    }
+
+    // number of lattice points on the edges of the polygon
+    pub fn boundary_points(&self) -> u64 {
+        self.edges()
+            .map(|(a, b)| gcd((b.0 - a.0).unsigned_abs(), (b.1 - a.1).unsigned_abs()))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside the polygon
+    ///
+    /// rearranges Pick's theorem `A = I + B / 2 - 1` into `I = (2A - B + 2) / 2`
+    pub fn interior_points(&self) -> u64 {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+
+        (self.double_area() + 2 - self.boundary_points()) / 2
+    }
+
+    // determine whether a point is inside, outside or on an edge of the polygon
+    pub fn locate(&self, point: Point) -> Location {
+        let mut inside = false;
+
+        for (a, b) in self.edges() {
+            let cross = (b.0 - a.0) * (point.1 - a.1) - (point.0 - a.0) * (b.1 - a.1);
+
+            let between = |p: i64, q: i64, x: i64| p.min(q) <= x && x <= p.max(q);
+            if cross == 0 && between(a.0, b.0, point.0) && between(a.1, b.1, point.1) {
+                return Location::Boundary;
+            }
+
+            // count edges crossed by a ray heading towards positive x
+            if (a.1 > point.1) != (b.1 > point.1) && (cross > 0) == (b.1 > a.1) {
+                inside = !inside;
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn area_and_lattice_points() {
+        let square = Polygon::new(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(32, square.signed_double_area());
+        assert_eq!(16, square.boundary_points());
+        assert_eq!(9, square.interior_points());
+
+        // clockwise only changes the sign
+        let triangle = Polygon::new(vec![(0, 0), (0, 3), (6, 0)]);
+        assert_eq!(-18, triangle.signed_double_area());
+        assert_eq!(3 + 3 + 6, triangle.boundary_points());
+        assert_eq!(4, triangle.interior_points());
+    }
+
+    #[test]
+    fn locate_points() {
+        // an L shape, with collinear vertices along the bottom
+        let polygon = Polygon::new(vec![(0, 0), (2, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+
+        assert_eq!(Location::Inside, polygon.locate((1, 1)));
+        assert_eq!(Location::Inside, polygon.locate((1, 3)));
+        assert_eq!(Location::Outside, polygon.locate((3, 3)));
+        assert_eq!(Location::Outside, polygon.locate((-1, 2)));
+        assert_eq!(Location::Boundary, polygon.locate((3, 0)));
+        assert_eq!(Location::Boundary, polygon.locate((2, 3)));
+        assert_eq!(Location::Boundary, polygon.locate((0, 0)));
+
+        // brute force agrees with Pick's theorem
+        let inside = (-1..6)
+            .flat_map(|x| (-1..6).map(move |y| (x, y)))
+            .filter(|x| polygon.locate(*x) == Location::Inside)
+            .count();
+        assert_eq!(polygon.interior_points(), inside as u64);
+    }
+}
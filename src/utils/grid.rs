@@ -0,0 +1,103 @@
+use super::parse::{self, ParseError};
+
+/// A rectangular grid of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per character, every line has to be the same length
+    pub fn parse<E: Into<String>>(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut cols = None;
+
+        for line in parse::lines(input) {
+            let mut len = 0;
+            for (i, c) in line.text.chars().enumerate() {
+                cells.push(cell(c).map_err(|e| line.error_at(i, e))?);
+                len += 1;
+            }
+
+            let cols = *cols.get_or_insert(len);
+            if len != cols {
+                return Err(
+                    line.error_at(len.min(cols), format!("expected a row of {} cells", cols))
+                );
+            }
+            rows += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            rows,
+            cols: cols.unwrap_or(0),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[(row * self.cols)..((row + 1) * self.cols)]
+    }
+
+    // the up to eight cells touching a cell, including diagonally
+    pub fn neighbours(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (-1isize..=1)
+            .flat_map(|dr| (-1isize..=1).map(move |dc| (dr, dc)))
+            .filter(|offset| *offset != (0, 0))
+            .filter_map(move |(dr, dc)| {
+                let r = row.checked_add_signed(dr)?;
+                let c = col.checked_add_signed(dc)?;
+                self.get(r, c).map(|_| (r, c))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_neighbours() {
+        let grid = Grid::parse("123\n456\n", |c| c.to_digit(10).ok_or("expected a digit")).unwrap();
+
+        assert_eq!((2, 3), (grid.rows(), grid.cols()));
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(Some(&6), grid.get(1, 2));
+        assert_eq!(None, grid.get(2, 0));
+        assert_eq!(
+            vec![(0, 1), (1, 0), (1, 1)],
+            grid.neighbours(0, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(5, grid.neighbours(1, 1).count());
+
+        let error = Grid::parse("12\n1x\n", |c| c.to_digit(10).ok_or("expected a digit"));
+        assert_eq!(Err(ParseError::new(2, 2, "expected a digit")), error);
+        let error = Grid::parse("12\n1\n", |c| c.to_digit(10).ok_or("expected a digit"));
+        assert_eq!(
+            Err(ParseError::new(2, 2, "expected a row of 2 cells")),
+            error
+        );
+    }
+}
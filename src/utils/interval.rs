@@ -0,0 +1,331 @@
+use std::ops::{Add, Sub};
+
+/// A half open interval `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    /// Constructs the interval `[start, end)`
+    ///
+    /// an `end` before `start` produces an empty interval
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    // determine whether every value of other is also in this interval
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    // determine whether the intervals share at least one value
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if self.overlaps(other) {
+            Some(Interval::new(
+                self.start.max(other.start),
+                self.end.min(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+
+    // joins two intervals, fails if there would be a gap between them
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if self.is_empty() {
+            Some(*other)
+        } else if other.is_empty() {
+            Some(*self)
+        } else if self.start.max(other.start) <= self.end.min(other.end) {
+            Some(Interval::new(
+                self.start.min(other.start),
+                self.end.max(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+
+    // returns the parts of this interval below and above other
+    pub fn difference(&self, other: &Interval<T>) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        if !self.overlaps(other) {
+            return if self.is_empty() {
+                (None, None)
+            } else {
+                (Some(*self), None)
+            };
+        }
+
+        let below = Interval::new(self.start, other.start);
+        let above = Interval::new(other.end, self.end);
+
+        (
+            Some(below).filter(|x| !x.is_empty()),
+            Some(above).filter(|x| !x.is_empty()),
+        )
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T>> Interval<T> {
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T>> Interval<T> {
+    // constructs an interval from a start and a number of values
+    pub fn with_len(start: T, len: T) -> Interval<T> {
+        Interval::new(start, start + len)
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non adjacent intervals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Copy + Ord> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet::new()
+    }
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // adds an interval, merging any intervals it touches
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // first interval that ends at or after the new start can be merged or comes after
+        let first = self.intervals.partition_point(|x| x.end < interval.start);
+        // first interval that starts after the new end can't be merged
+        let last = self.intervals.partition_point(|x| x.start <= interval.end);
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |acc, x| acc.union(x).unwrap());
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    // removes every value in interval from the set
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|x| x.end <= interval.start);
+        let last = self.intervals.partition_point(|x| x.start < interval.end);
+
+        let remaining = self.intervals[first..last]
+            .iter()
+            .flat_map(|x| {
+                let (below, above) = x.difference(&interval);
+                below.into_iter().chain(above)
+            })
+            .collect::<Vec<_>>();
+
+        self.intervals.splice(first..last, remaining);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|x| x.end <= value);
+        self.intervals.get(index).is_some_and(|x| x.contains(value))
+    }
+
+    // determine whether every value of interval is in the set
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+
+        let index = self.intervals.partition_point(|x| x.end <= interval.start);
+        self.intervals
+            .get(index)
+            .is_some_and(|x| x.contains_interval(interval))
+    }
+
+    // determine whether any value of interval is in the set
+    pub fn overlaps(&self, interval: &Interval<T>) -> bool {
+        let index = self.intervals.partition_point(|x| x.end <= interval.start);
+        self.intervals
+            .get(index)
+            .is_some_and(|x| x.overlaps(interval))
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+
+        // walk both sorted lists together
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (&self.intervals[a], &other.intervals[b]);
+            if let Some(overlap) = x.intersection(y) {
+                intervals.push(overlap);
+            }
+
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        RangeSet { intervals }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.remove(*interval);
+        }
+        result
+    }
+
+    // iterate over the disjoint pieces of the set in ascending order
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    // smallest value in the set
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|x| x.start)
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + std::iter::Sum> RangeSet<T> {
+    // total number of values in the set
+    pub fn total_len(&self) -> T {
+        self.intervals.iter().map(|x| x.len()).sum()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl<T: Copy + Ord> From<Interval<T>> for RangeSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let mut set = RangeSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RangeSet<T> {
+    type Item = &'a Interval<T>;
+    type IntoIter = std::slice::Iter<'a, Interval<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interval_set_operations() {
+        let a = Interval::new(2, 7);
+        let b = Interval::new(5, 10);
+
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&Interval::new(7, 9)));
+        assert_eq!(Some(Interval::new(5, 7)), a.intersection(&b));
+        assert_eq!(Some(Interval::new(2, 10)), a.union(&b));
+        assert_eq!(None, a.union(&Interval::new(8, 9)));
+        assert_eq!((Some(Interval::new(2, 5)), None), a.difference(&b));
+        assert_eq!(5, a.len());
+    }
+
+    #[test]
+    fn range_set_normalises() {
+        let set: RangeSet<i32> = [
+            Interval::new(10, 12),
+            Interval::new(0, 3),
+            Interval::new(3, 5),
+            Interval::new(11, 15),
+        ]
+        .into_iter()
+        .collect();
+
+        let pieces = set.iter().copied().collect::<Vec<_>>();
+        assert_eq!(vec![Interval::new(0, 5), Interval::new(10, 15)], pieces);
+        assert_eq!(10, set.total_len());
+        assert!(set.contains(4));
+        assert!(!set.contains(5));
+        assert!(set.contains_interval(&Interval::new(11, 14)));
+        assert!(!set.contains_interval(&Interval::new(4, 11)));
+    }
+
+    #[test]
+    fn range_set_combinations() {
+        let a: RangeSet<i32> = [Interval::new(0, 10), Interval::new(20, 30)]
+            .into_iter()
+            .collect();
+        let b: RangeSet<i32> = [Interval::new(5, 25)].into_iter().collect();
+
+        let intersection = a.intersection(&b).iter().copied().collect::<Vec<_>>();
+        assert_eq!(
+            vec![Interval::new(5, 10), Interval::new(20, 25)],
+            intersection
+        );
+
+        let difference = a.difference(&b).iter().copied().collect::<Vec<_>>();
+        assert_eq!(vec![Interval::new(0, 5), Interval::new(25, 30)], difference);
+
+        assert_eq!(30, a.union(&b).total_len());
+    }
+}
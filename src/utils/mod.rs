@@ -0,0 +1,10 @@
+pub mod interval;
+pub mod polynomial;
+pub mod quadratic;
+pub mod partial_order;
+pub mod parse;
+pub mod geometry;
+pub mod word_search;
+pub mod trie;
+pub mod grid;
+pub mod bitset;
@@ -0,0 +1,287 @@
+use std::{error::Error, fmt, io::BufRead, ops::Range, str::FromStr};
+
+/// An error in the puzzle input, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A line of input, or a piece of one, which remembers where it came from
+///
+/// pieces are taken with the methods here so they keep their byte offset
+/// into the line, and errors built from them point at the right column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    // byte offset of text from the start of the whole line
+    pub offset: usize,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Line<'a> {
+        Line {
+            number,
+            text,
+            offset: 0,
+        }
+    }
+
+    // a piece of this line, by byte range into text
+    pub fn slice(&self, range: Range<usize>) -> Line<'a> {
+        Line {
+            number: self.number,
+            text: &self.text[range.start..range.end],
+            offset: self.offset + range.start,
+        }
+    }
+
+    pub fn trim(&self) -> Line<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start..end)
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Line<'a>> {
+        self.text
+            .starts_with(prefix)
+            .then(|| self.slice(prefix.len()..self.text.len()))
+    }
+
+    pub fn strip_suffix(&self, suffix: &str) -> Option<Line<'a>> {
+        self.text
+            .ends_with(suffix)
+            .then(|| self.slice(0..self.text.len() - suffix.len()))
+    }
+
+    // the pieces between separators, like `str::split`
+    pub fn split(&self, separator: char) -> impl Iterator<Item = Line<'a>> {
+        let line = *self;
+        let mut start = 0;
+
+        line.text.split(separator).map(move |piece| {
+            let piece = line.slice(start..start + piece.len());
+            start += piece.text.len() + separator.len_utf8();
+            piece
+        })
+    }
+
+    // the whitespace separated pieces, like `str::split_whitespace`
+    pub fn words(&self) -> impl Iterator<Item = Line<'a>> {
+        let line = *self;
+        let mut end = 0;
+
+        std::iter::from_fn(move || {
+            let start = end + line.text[end..].find(|c: char| !c.is_whitespace())?;
+            end = line.text[start..]
+                .find(char::is_whitespace)
+                .map_or(line.text.len(), |x| start + x);
+            Some(line.slice(start..end))
+        })
+    }
+
+    // builds an error pointing at the start of this piece of the line
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(0, message)
+    }
+
+    // builds an error pointing at a byte index of this piece of the line
+    pub fn error_at(&self, index: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, self.offset + index + 1, message)
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|_| self.error(format!("invalid value `{}`", self.text)))
+    }
+
+    // parses every whitespace separated token
+    pub fn tokens<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        self.words().map(|token| token.parse()).collect()
+    }
+
+    /// Extracts every integer from the line, ignoring any other text
+    ///
+    /// a `-` is only read as a sign when it isn't preceded by a digit, so
+    /// ranges like `2-4` give `[2, 4]`
+    pub fn integers<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut integers = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let signed = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+
+            if !signed && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+
+            integers.push(self.slice(start..i).parse()?);
+        }
+
+        Ok(integers)
+    }
+
+    // splits around the first separator, trimming both sides
+    pub fn split_once(&self, separator: char) -> Result<(Line<'a>, Line<'a>), ParseError> {
+        let index = self
+            .text
+            .find(separator)
+            .ok_or_else(|| self.error_at(self.text.len(), format!("expected `{}`", separator)))?;
+
+        let left = self.slice(0..index);
+        let right = self.slice(index + separator.len_utf8()..self.text.len());
+        Ok((left.trim(), right.trim()))
+    }
+
+    // splits a `key: values` line
+    pub fn key_values(&self) -> Result<(Line<'a>, Line<'a>), ParseError> {
+        self.split_once(':')
+    }
+}
+
+// numbers every line of the input
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line::new(i + 1, text))
+}
+
+// splits input into blank line separated sections
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = vec![Vec::new()];
+
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            sections.push(Vec::new());
+        } else {
+            sections.last_mut().unwrap().push(line);
+        }
+    }
+
+    sections.retain(|section| !section.is_empty());
+    sections
+}
+
+/// Reads the whole input into one string, reporting where reading failed
+///
+/// lines are sliced out of the string afterwards, so nothing is allocated per line
+pub fn read_input(mut reader: impl BufRead) -> Result<String, ParseError> {
+    let mut bytes = Vec::new();
+
+    // everything read before an error is kept, so it can be located
+    if let Err(e) = reader.read_to_end(&mut bytes) {
+        return Err(error_after(&bytes, e.to_string()));
+    }
+
+    String::from_utf8(bytes).map_err(|e| {
+        let valid = e.utf8_error().valid_up_to();
+        error_after(&e.as_bytes()[..valid], "input isn't valid UTF-8")
+    })
+}
+
+// an error at the position just after the bytes read so far
+fn error_after(read: &[u8], message: impl Into<String>) -> ParseError {
+    let line_start = read.iter().rposition(|x| *x == b'\n').map_or(0, |i| i + 1);
+    let line = read.iter().filter(|x| **x == b'\n').count() + 1;
+
+    ParseError::new(line, read.len() - line_start + 1, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_integers() {
+        let line = Line::new(1, "move -3 from 2-4 to x17,-8");
+
+        assert_eq!(vec![-3, 2, 4, 17, -8], line.integers::<i32>().unwrap());
+        assert_eq!(
+            Err(ParseError::new(1, 6, "invalid value `-3`")),
+            line.integers::<u32>()
+        );
+    }
+
+    #[test]
+    fn read_errors_report_position() {
+        assert_eq!(Ok("a\nbc".to_string()), read_input("a\nbc".as_bytes()));
+        assert_eq!(
+            Err(ParseError::new(2, 3, "input isn't valid UTF-8")),
+            read_input(&b"a\nbc\xff"[..])
+        );
+    }
+
+    #[test]
+    fn split_sections() {
+        let input = "1\n2\n\n3\n\n\n4\n";
+        let sections = sections(input);
+
+        assert_eq!(3, sections.len());
+        assert_eq!(
+            vec!["1", "2"],
+            sections[0].iter().map(|x| x.text).collect::<Vec<_>>()
+        );
+        assert_eq!(7, sections[2][0].number);
+    }
+
+    #[test]
+    fn key_values_report_position() {
+        let line = Line::new(4, "Time:  7 15 x");
+        let (key, values) = line.key_values().unwrap();
+        assert_eq!("Time", key.text);
+        assert_eq!("7 15 x", values.text);
+
+        let error = values.tokens::<u32>().unwrap_err();
+        assert_eq!(ParseError::new(4, 13, "invalid value `x`"), error);
+
+        // pieces of pieces still know their column
+        let (_, last) = values.split_once('5').unwrap();
+        assert_eq!(
+            Err(ParseError::new(4, 13, "invalid value `x`")),
+            last.parse::<u32>()
+        );
+        let pieces: Vec<_> = Line::new(1, "a,,b").split(',').map(|x| x.offset).collect();
+        assert_eq!(vec![0, 2, 3], pieces);
+
+        assert_eq!(
+            Err(ParseError::new(2, 3, "expected `:`")),
+            Line::new(2, "ab").key_values()
+        );
+    }
+}
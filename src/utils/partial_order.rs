@@ -0,0 +1,224 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    hash::Hash,
+};
+
+/// Items whose rules form a cycle, each one has to come before the next and
+/// the last before the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T> {
+    pub items: Vec<T>,
+}
+
+impl<T: fmt::Debug> fmt::Display for Cycle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ordering rules form a cycle between {:?}", self.items)
+    }
+}
+
+/// A set of "before must come before after" rules
+#[derive(Debug, Clone)]
+pub struct PrecedenceGraph<T> {
+    successors: HashMap<T, HashSet<T>>,
+}
+
+impl<T: Copy + Eq + Hash> Default for PrecedenceGraph<T> {
+    fn default() -> Self {
+        PrecedenceGraph::new()
+    }
+}
+
+impl<T: Copy + Eq + Hash> PrecedenceGraph<T> {
+    pub fn new() -> PrecedenceGraph<T> {
+        PrecedenceGraph {
+            successors: HashMap::new(),
+        }
+    }
+
+    pub fn add_rule(&mut self, before: T, after: T) {
+        self.successors.entry(before).or_default().insert(after);
+    }
+
+    // determine whether there is a rule placing before ahead of after
+    pub fn precedes(&self, before: T, after: T) -> bool {
+        self.successors
+            .get(&before)
+            .is_some_and(|x| x.contains(&after))
+    }
+
+    /// Compares two items by their direct rules, for use with `sort_by`
+    ///
+    /// items without a rule between them compare equal, so this only gives a
+    /// correct sort when every pair in the slice has a rule
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        if self.precedes(*a, *b) {
+            Ordering::Less
+        } else if self.precedes(*b, *a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    // determine whether items breaks no rules
+    pub fn is_ordered(&self, items: &[T]) -> bool {
+        let mut seen = HashSet::new();
+
+        for item in items {
+            // an item seen earlier has to come after this one
+            if let Some(successors) = self.successors.get(item) {
+                if successors.iter().any(|x| seen.contains(x)) {
+                    return false;
+                }
+            }
+            seen.insert(*item);
+        }
+
+        true
+    }
+
+    /// Orders items using only the rules between them (Kahn's algorithm)
+    ///
+    /// ties are broken by the original order of items, and a repeated item is
+    /// only kept at its first position
+    pub fn sort(&self, items: &[T]) -> Result<Vec<T>, Cycle<T>> {
+        let mut subset: HashSet<T> = HashSet::new();
+        let items: Vec<T> = items
+            .iter()
+            .copied()
+            .filter(|x| subset.insert(*x))
+            .collect();
+
+        // count the predecessors of each item within the subset
+        let mut predecessors: HashMap<T, usize> = items.iter().map(|x| (*x, 0)).collect();
+        for item in items.iter() {
+            for successor in self.successors_in(item, &subset) {
+                *predecessors.get_mut(successor).unwrap() += 1;
+            }
+        }
+
+        let mut queue: VecDeque<T> = items
+            .iter()
+            .filter(|x| predecessors[*x] == 0)
+            .copied()
+            .collect();
+        let mut sorted = Vec::with_capacity(items.len());
+
+        while let Some(item) = queue.pop_front() {
+            sorted.push(item);
+
+            for successor in self.successors_in(&item, &subset) {
+                let count = predecessors.get_mut(successor).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    queue.push_back(*successor);
+                }
+            }
+        }
+
+        if sorted.len() < items.len() {
+            return Err(self.find_cycle(&items, &subset, &predecessors));
+        }
+
+        Ok(sorted)
+    }
+
+    /// Finds a cycle among the items left over by `sort`
+    ///
+    /// every leftover item still waits on another leftover item, so walking
+    /// back through waiting predecessors has to reach an item a second time,
+    /// and everything walked since then is the cycle
+    fn find_cycle(
+        &self,
+        items: &[T],
+        subset: &HashSet<T>,
+        waiting: &HashMap<T, usize>,
+    ) -> Cycle<T> {
+        let blocked = |x: &T| waiting[x] > 0;
+
+        let mut previous: HashMap<T, T> = HashMap::new();
+        for item in items.iter().filter(|x| blocked(x)) {
+            for successor in self.successors_in(item, subset).filter(|x| blocked(x)) {
+                previous.entry(*successor).or_insert(*item);
+            }
+        }
+
+        let mut walked = vec![*items.iter().find(|x| blocked(x)).unwrap()];
+        loop {
+            let next = previous[walked.last().unwrap()];
+            if let Some(start) = walked.iter().position(|x| *x == next) {
+                // the walk went backwards, reverse it into rule order
+                let mut items = walked.split_off(start);
+                items.reverse();
+                return Cycle { items };
+            }
+            walked.push(next);
+        }
+    }
+
+    fn successors_in<'a>(
+        &'a self,
+        item: &T,
+        subset: &'a HashSet<T>,
+    ) -> impl Iterator<Item = &'a T> + 'a {
+        self.successors
+            .get(item)
+            .into_iter()
+            .flatten()
+            .filter(|x| subset.contains(*x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorts_subset() {
+        let mut graph = PrecedenceGraph::new();
+        graph.add_rule(1, 2);
+        graph.add_rule(2, 3);
+        graph.add_rule(1, 3);
+        graph.add_rule(4, 1);
+
+        assert!(graph.is_ordered(&[1, 2, 3]));
+        assert!(!graph.is_ordered(&[3, 1]));
+        assert_eq!(Ok(vec![1, 2, 3]), graph.sort(&[3, 2, 1]));
+
+        let mut items = vec![3, 1, 2];
+        items.sort_by(|a, b| graph.compare(a, b));
+        assert_eq!(vec![1, 2, 3], items);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let mut graph = PrecedenceGraph::new();
+        graph.add_rule(1, 2);
+        graph.add_rule(2, 3);
+        graph.add_rule(3, 2);
+        // 4 waits on the cycle without being part of it
+        graph.add_rule(3, 4);
+        graph.add_rule(4, 5);
+        graph.add_rule(5, 6);
+        graph.add_rule(6, 4);
+
+        assert_eq!(Err(Cycle { items: vec![2, 3] }), graph.sort(&[3, 2, 1]));
+        assert_eq!(Err(Cycle { items: vec![2, 3] }), graph.sort(&[4, 3, 2, 1]));
+        assert_eq!(
+            Err(Cycle {
+                items: vec![4, 5, 6]
+            }),
+            graph.sort(&[6, 5, 4])
+        );
+    }
+
+    #[test]
+    fn repeated_items() {
+        let mut graph = PrecedenceGraph::new();
+        graph.add_rule(1, 2);
+
+        assert_eq!(Ok(vec![1, 2]), graph.sort(&[2, 1, 2, 1]));
+    }
+}
@@ -0,0 +1,339 @@
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceError {
+    // there are no values to extrapolate from
+    Empty,
+    // the differences never reached a row of zeros, so the degree can't be determined
+    NeverZero,
+    // a value or coefficient doesn't fit in an i128
+    Overflow,
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::Empty => write!(f, "sequence is empty"),
+            SequenceError::NeverZero => {
+                write!(f, "sequence differences never reach all zeros")
+            }
+            SequenceError::Overflow => write!(f, "sequence value overflowed"),
+        }
+    }
+}
+
+/// A sequence of integers generated by a polynomial
+///
+/// Stored as the Newton forward differences of the first value, so that
+/// `value(n) = sum(differences[k] * C(n, k))`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolynomialSequence {
+    differences: Vec<i128>,
+    len: usize,
+}
+
+impl PolynomialSequence {
+    pub fn new(values: &[i64]) -> Result<PolynomialSequence, SequenceError> {
+        if values.is_empty() {
+            return Err(SequenceError::Empty);
+        }
+
+        let mut row: Vec<i128> = values.iter().map(|x| *x as i128).collect();
+        let mut differences = Vec::new();
+
+        // take differences until a non empty row of zeros is found
+        while !row.iter().all(|x| *x == 0) {
+            if row.len() == 1 {
+                return Err(SequenceError::NeverZero);
+            }
+
+            differences.push(row[0]);
+            row = row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<_>>()
+                .ok_or(SequenceError::Overflow)?;
+        }
+
+        Ok(PolynomialSequence {
+            differences,
+            len: values.len(),
+        })
+    }
+
+    // degree of the generating polynomial, an all zero sequence has degree 0
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    /// Value at index n, where the first value of the sequence is at index 0
+    ///
+    /// errors instead of wrapping when the value, or a binomial coefficient
+    /// on the way to it, doesn't fit in an i128
+    pub fn value_at(&self, n: i64) -> Result<i128, SequenceError> {
+        let n = n as i128;
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+
+        for (k, difference) in self.differences.iter().enumerate() {
+            value = difference
+                .checked_mul(binomial)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(SequenceError::Overflow)?;
+            if k + 1 == self.differences.len() {
+                break;
+            }
+
+            // C(n, k + 1) from C(n, k), dividing before multiplying so it
+            // only overflows when the coefficient itself doesn't fit
+            let divisor = gcd(binomial, k as i128 + 1);
+            binomial = (binomial / divisor)
+                .checked_mul((n - k as i128) / ((k as i128 + 1) / divisor))
+                .ok_or(SequenceError::Overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    // the value steps after the last value of the sequence
+    pub fn forward(&self, steps: usize) -> Result<i128, SequenceError> {
+        let n = i64::try_from(self.len - 1 + steps).map_err(|_| SequenceError::Overflow)?;
+        self.value_at(n)
+    }
+
+    // the value steps before the first value of the sequence
+    pub fn backward(&self, steps: usize) -> Result<i128, SequenceError> {
+        let n = i64::try_from(steps).map_err(|_| SequenceError::Overflow)?;
+        self.value_at(-n)
+    }
+
+    // leading forward differences, the coefficients of the Newton form
+    pub fn newton_coefficients(&self) -> &[i128] {
+        &self.differences
+    }
+
+    // coefficients of the polynomial in n, from the constant term upwards
+    pub fn coefficients(&self) -> Result<Vec<Rational>, SequenceError> {
+        let mut coefficients = vec![Rational::from(0); self.differences.len().max(1)];
+        // n(n - 1)...(n - k + 1), lowest power first
+        let mut falling = vec![1i128];
+        let mut factorial: i128 = 1;
+
+        for (k, difference) in self.differences.iter().enumerate() {
+            for (power, term) in falling.iter().enumerate() {
+                coefficients[power] = difference
+                    .checked_mul(*term)
+                    .and_then(|x| coefficients[power].checked_add(Rational::new(x, factorial)))
+                    .ok_or(SequenceError::Overflow)?;
+            }
+
+            // multiply falling by (n - k)
+            let mut next = vec![0i128; falling.len() + 1];
+            for (power, term) in falling.iter().enumerate() {
+                next[power + 1] = next[power + 1]
+                    .checked_add(*term)
+                    .ok_or(SequenceError::Overflow)?;
+                next[power] = term
+                    .checked_mul(k as i128)
+                    .and_then(|x| next[power].checked_sub(x))
+                    .ok_or(SequenceError::Overflow)?;
+            }
+            falling = next;
+            factorial = factorial
+                .checked_mul(k as i128 + 1)
+                .ok_or(SequenceError::Overflow)?;
+        }
+
+        Ok(coefficients)
+    }
+
+    // evaluates the polynomial at any rational point
+    pub fn evaluate(&self, x: Rational) -> Result<Rational, SequenceError> {
+        self.coefficients()?
+            .into_iter()
+            .rev()
+            .try_fold(Rational::from(0), |acc, coefficient| {
+                acc.checked_mul(x)?.checked_add(coefficient)
+            })
+            .ok_or(SequenceError::Overflow)
+    }
+}
+
+/// An exact fraction, always stored in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        assert!(denominator != 0, "denominator can't be zero");
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Rational {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    // None when the sum doesn't fit in an i128 fraction
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let numerator = self
+            .numerator
+            .checked_mul(rhs.denominator)?
+            .checked_add(rhs.numerator.checked_mul(self.denominator)?)?;
+        Some(Rational::new(
+            numerator,
+            self.denominator.checked_mul(rhs.denominator)?,
+        ))
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Some(Rational::new(
+            self.numerator.checked_neg()?,
+            self.denominator,
+        ))
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    // None when the product doesn't fit in an i128 fraction
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        Some(Rational::new(
+            self.numerator.checked_mul(rhs.numerator)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        ))
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::new(value, 1)
+    }
+}
+
+// the operators panic on overflow in every build, use the checked methods
+// when the values could get that large
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        self.checked_add(rhs).expect("rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        self.checked_sub(rhs)
+            .expect("rational subtraction overflowed")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        self.checked_neg().expect("rational negation overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        self.checked_mul(rhs)
+            .expect("rational multiplication overflowed")
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extrapolate_both_directions() {
+        // n^2 + n + 1
+        let sequence = PolynomialSequence::new(&[1, 3, 7, 13, 21]).unwrap();
+
+        assert_eq!(2, sequence.degree());
+        assert_eq!(Ok(31), sequence.forward(1));
+        assert_eq!(Ok(57), sequence.forward(3));
+        assert_eq!(Ok(1), sequence.backward(1));
+        assert_eq!(Ok(3), sequence.backward(2));
+    }
+
+    #[test]
+    fn closed_form_coefficients() {
+        // triangular numbers, n^2 / 2 + n / 2
+        let sequence = PolynomialSequence::new(&[0, 1, 3, 6, 10]).unwrap();
+
+        assert_eq!(
+            vec![Rational::from(0), Rational::new(1, 2), Rational::new(1, 2)],
+            sequence.coefficients().unwrap()
+        );
+        assert_eq!(
+            Ok(Rational::new(3, 8)),
+            sequence.evaluate(Rational::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn unresolved_sequence_errors() {
+        assert_eq!(Err(SequenceError::Empty), PolynomialSequence::new(&[]));
+        assert_eq!(
+            Err(SequenceError::NeverZero),
+            PolynomialSequence::new(&[1, 2, 4, 8])
+        );
+
+        // n^4 is past i128 long before n reaches i64::MAX
+        let sequence = PolynomialSequence::new(&[0, 1, 16, 81, 256, 625]).unwrap();
+        assert_eq!(Err(SequenceError::Overflow), sequence.value_at(i64::MAX));
+
+        // n^2 at 2^62 only just fits, without overflowing on the way there
+        let sequence = PolynomialSequence::new(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(Ok(1 << 124), sequence.value_at(1 << 62));
+        assert_eq!(
+            None,
+            Rational::from(i128::MAX).checked_add(Rational::from(1))
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use super::interval::Interval;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuadraticError {
+    // an intermediate value doesn't fit in a u128
+    Overflow,
+}
+
+impl fmt::Display for QuadraticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuadraticError::Overflow => write!(f, "quadratic overflowed u128 intermediates"),
+        }
+    }
+}
+
+/// Finds every integer x where `x^2 - b*x + c < 0`
+///
+/// Equivalently the x in `[0, b]` where `x * (b - x) > c`. Uses only integer
+/// arithmetic, so the bounds are exact for any input that fits.
+pub fn negative_interval(b: u128, c: u128) -> Result<Interval<u128>, QuadraticError> {
+    let square = b.checked_mul(b).ok_or(QuadraticError::Overflow)?;
+    let four_c = c.checked_mul(4).ok_or(QuadraticError::Overflow)?;
+
+    // negative discriminant, no real roots
+    let Some(discriminant) = square.checked_sub(four_c) else {
+        return Ok(Interval::new(0, 0));
+    };
+
+    // x <= b, so the product is at most b^2 / 4 and can't overflow
+    let exceeds = |x: u128| x * (b - x) > c;
+    let midpoint = b / 2;
+
+    // the parabola peaks at the midpoint, nothing can be below zero if it isn't
+    if !exceeds(midpoint) {
+        return Ok(Interval::new(0, 0));
+    }
+
+    // estimate the lower root, then correct the rounding from the square root
+    let mut lower = (b - discriminant.isqrt()) / 2;
+    while !exceeds(lower) {
+        lower += 1;
+    }
+    while lower > 0 && exceeds(lower - 1) {
+        lower -= 1;
+    }
+
+    // symmetric around b / 2, so the upper bound mirrors the lower one
+    Ok(Interval::new(lower, b - lower + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_brute_force() {
+        for b in 0..60u128 {
+            for c in 0..1000u128 {
+                let expected = (0..=b).filter(|x| x * (b - x) > c).collect::<Vec<_>>();
+                let interval = negative_interval(b, c).unwrap();
+
+                assert_eq!(expected.len() as u128, interval.len());
+                if let Some(first) = expected.first() {
+                    assert_eq!(*first, interval.start());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_near_float_precision() {
+        // 2^53 + 1 can't be represented by an f64
+        let b = (1u128 << 53) + 1;
+        let c = (1u128 << 52) - 3;
+        let interval = negative_interval(b, c).unwrap();
+        let lower = interval.start();
+
+        assert!(lower * (b - lower) > c);
+        assert!((lower - 1) * (b - lower + 1) <= c);
+
+        let b = u64::MAX as u128;
+        let c = b * 1000;
+        let lower = negative_interval(b, c).unwrap().start();
+        assert!(lower * (b - lower) > c);
+        assert!((lower - 1) * (b - lower + 1) <= c);
+    }
+
+    #[test]
+    fn overflow_errors() {
+        assert_eq!(
+            Err(QuadraticError::Overflow),
+            negative_interval(u128::MAX, 1)
+        );
+    }
+}
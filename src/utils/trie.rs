@@ -0,0 +1,139 @@
+/// A set of words with a value attached to each, for finding them inside text
+///
+/// words are matched byte by byte, so any UTF-8 vocabulary works. Searching
+/// text starts a fresh walk from the root at every position, so a search
+/// costs up to the text length times the longest word, which is fine for
+/// short words but not an Aho-Corasick automaton
+#[derive(Debug, Clone)]
+pub struct Trie<V> {
+    nodes: Vec<Node<V>>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    children: Vec<(u8, usize)>,
+    value: Option<V>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Node<V> {
+        Node {
+            children: Vec::new(),
+            value: None,
+        }
+    }
+
+    fn child(&self, byte: u8) -> Option<usize> {
+        self.children
+            .iter()
+            .find(|(x, _)| *x == byte)
+            .map(|(_, node)| *node)
+    }
+}
+
+impl<V: Copy> Default for Trie<V> {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+impl<V: Copy> Trie<V> {
+    pub fn new() -> Trie<V> {
+        Trie {
+            nodes: vec![Node::new()],
+        }
+    }
+
+    // adds a word, replacing the value of a word that was already added
+    pub fn insert(&mut self, word: &str, value: V) {
+        let mut current = 0;
+
+        for byte in word.bytes() {
+            current = match self.nodes[current].child(byte) {
+                Some(next) => next,
+                None => {
+                    self.nodes.push(Node::new());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[current].children.push((byte, next));
+                    next
+                }
+            };
+        }
+
+        self.nodes[current].value = Some(value);
+    }
+
+    /// The longest word that text starts with, and its length in bytes
+    pub fn longest_prefix(&self, text: &[u8]) -> Option<(V, usize)> {
+        let mut current = 0;
+        let mut longest = None;
+
+        for (i, byte) in text.iter().enumerate() {
+            let Some(next) = self.nodes[current].child(*byte) else {
+                break;
+            };
+            current = next;
+
+            if let Some(value) = self.nodes[current].value {
+                longest = Some((value, i + 1));
+            }
+        }
+
+        longest
+    }
+
+    // the word starting earliest in text, with its byte position
+    pub fn first_match(&self, text: &str) -> Option<(usize, V)> {
+        let bytes = text.as_bytes();
+        (0..bytes.len()).find_map(|i| self.longest_prefix(&bytes[i..]).map(|(x, _)| (i, x)))
+    }
+
+    // the word starting latest in text, with its byte position
+    pub fn last_match(&self, text: &str) -> Option<(usize, V)> {
+        let bytes = text.as_bytes();
+        (0..bytes.len())
+            .rev()
+            .find_map(|i| self.longest_prefix(&bytes[i..]).map(|(x, _)| (i, x)))
+    }
+
+    // every position a word starts at, words may overlap
+    pub fn matches<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (usize, V)> + 'a {
+        let bytes = text.as_bytes();
+        (0..bytes.len()).filter_map(|i| self.longest_prefix(&bytes[i..]).map(|(x, _)| (i, x)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn overlapping_words() {
+        let mut trie = Trie::new();
+        for (value, word) in ["one", "two", "eight", "eighteen"].iter().enumerate() {
+            trie.insert(word, value);
+        }
+
+        let text = "xeightwone";
+        assert_eq!(Some((1, 2)), trie.first_match(text));
+        assert_eq!(Some((7, 0)), trie.last_match(text));
+        assert_eq!(
+            vec![(1, 2), (5, 1), (7, 0)],
+            trie.matches(text).collect::<Vec<_>>()
+        );
+
+        // the longest word wins when several start at the same place
+        assert_eq!(Some((3, 8)), trie.longest_prefix(b"eighteens"));
+        assert_eq!(None, trie.first_match("nothing here"));
+    }
+
+    #[test]
+    fn multibyte_words() {
+        let mut trie = Trie::new();
+        trie.insert("fünf", 5);
+        trie.insert("drei", 3);
+
+        assert_eq!(Some((0, 3)), trie.first_match("dreifünf"));
+        assert_eq!(Some((4, 5)), trie.last_match("dreifünf"));
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+/// The eight directions a word can be read in, clockwise from north
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    // row and column step
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::NorthEast => (-1, 1),
+            Direction::East => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (1, 0),
+            Direction::SouthWest => (1, -1),
+            Direction::West => (0, -1),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+}
+
+/// A shape of letters to search for, cells left out match anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    // offsets from the first row and column of the pattern
+    cells: Vec<((isize, isize), char)>,
+}
+
+impl Pattern {
+    /// Builds a pattern from rows of text, with `wildcard` matching any letter
+    pub fn new(rows: &[&str], wildcard: char) -> Pattern {
+        let cells = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, text)| {
+                text.chars()
+                    .enumerate()
+                    .filter(move |(_, c)| *c != wildcard)
+                    .map(move |(col, c)| ((row as isize, col as isize), c))
+            })
+            .collect();
+
+        Pattern { cells }
+    }
+
+    // a word read from its first letter in a direction
+    pub fn word(word: &str, direction: Direction) -> Pattern {
+        let (dr, dc) = direction.offset();
+        let cells = word
+            .chars()
+            .enumerate()
+            .map(|(i, c)| ((dr * i as isize, dc * i as isize), c))
+            .collect();
+
+        Pattern { cells }
+    }
+
+    // the pattern turned a quarter turn clockwise around its first cell
+    pub fn rotate(&self) -> Pattern {
+        let cells = self
+            .cells
+            .iter()
+            .map(|((row, col), c)| ((*col, -row), *c))
+            .collect();
+
+        Pattern { cells }
+    }
+
+    // cells moved to start at row and column 0, to compare shapes
+    fn normalized(&self) -> Vec<((isize, isize), char)> {
+        let min_row = self.cells.iter().map(|x| x.0 .0).min().unwrap_or(0);
+        let min_col = self.cells.iter().map(|x| x.0 .1).min().unwrap_or(0);
+
+        self.cells
+            .iter()
+            .map(|((row, col), c)| ((row - min_row, col - min_col), *c))
+            .sorted()
+            .collect()
+    }
+}
+
+/// Where a word or pattern was found
+///
+/// `position` is where the first cell of the word or pattern landed, for
+/// patterns the direction is where the top of the pattern points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub position: (usize, usize),
+    pub direction: Direction,
+}
+
+/// A grid of letters to search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSearch {
+    grid: Vec<Vec<char>>,
+}
+
+impl WordSearch {
+    pub fn new(grid: Vec<Vec<char>>) -> WordSearch {
+        WordSearch { grid }
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> Option<char> {
+        let row = self.grid.get(usize::try_from(row).ok()?)?;
+        row.get(usize::try_from(col).ok()?).copied()
+    }
+
+    // finds a word read in any of the eight directions
+    pub fn find_word(&self, word: &str, overlaps: bool) -> Vec<Match> {
+        let orientations = Direction::ALL
+            .into_iter()
+            .map(|direction| (Pattern::word(word, direction), direction))
+            .collect();
+
+        self.search(orientations, overlaps)
+    }
+
+    // finds a pattern in any of its four quarter turns
+    pub fn find_pattern(&self, pattern: &Pattern, overlaps: bool) -> Vec<Match> {
+        let mut orientations = Vec::new();
+        let mut rotated = pattern.clone();
+        for direction in [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ] {
+            let next = rotated.rotate();
+            orientations.push((rotated, direction));
+            rotated = next;
+        }
+
+        self.search(orientations, overlaps)
+    }
+
+    /// Checks every orientation at every cell
+    ///
+    /// orientations covering the same shape as an earlier one are skipped, so
+    /// a palindrome or symmetric pattern is only found once. Without overlaps
+    /// a match can't reuse any cell of an earlier match, in reading order
+    fn search(&self, orientations: Vec<(Pattern, Direction)>, overlaps: bool) -> Vec<Match> {
+        let mut shapes = HashSet::new();
+        let orientations: Vec<_> = orientations
+            .into_iter()
+            .filter(|(pattern, _)| shapes.insert(pattern.normalized()))
+            .collect();
+
+        let mut used = HashSet::new();
+        let mut matches = Vec::new();
+
+        for (row, letters) in self.grid.iter().enumerate() {
+            for col in 0..letters.len() {
+                for (pattern, direction) in orientations.iter() {
+                    let Some(cells) = self.cells_at(pattern, (row, col)) else {
+                        continue;
+                    };
+
+                    if !overlaps {
+                        if cells.iter().any(|x| used.contains(x)) {
+                            continue;
+                        }
+                        used.extend(cells);
+                    }
+
+                    matches.push(Match {
+                        position: (row, col),
+                        direction: *direction,
+                    });
+                }
+            }
+        }
+
+        matches
+    }
+
+    // cells covered by the pattern placed at position, if every letter matches
+    fn cells_at(&self, pattern: &Pattern, position: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        pattern
+            .cells
+            .iter()
+            .map(|((dr, dc), c)| {
+                let row = position.0 as isize + dr;
+                let col = position.1 as isize + dc;
+                (self.get(row, col)? == *c).then_some((row as usize, col as usize))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> WordSearch {
+        WordSearch::new(rows.iter().map(|x| x.chars().collect()).collect())
+    }
+
+    #[test]
+    fn words_in_every_direction() {
+        let search = grid(&["CAT", "AAA", "TAC"]);
+        let matches = search.find_word("CAT", true);
+
+        assert_eq!(
+            vec![
+                Match {
+                    position: (0, 0),
+                    direction: Direction::East
+                },
+                Match {
+                    position: (0, 0),
+                    direction: Direction::South
+                },
+                Match {
+                    position: (2, 2),
+                    direction: Direction::North
+                },
+                Match {
+                    position: (2, 2),
+                    direction: Direction::West
+                },
+            ],
+            matches
+        );
+
+        // the words along the edges share their corners
+        assert_eq!(2, search.find_word("CAT", false).len());
+        // palindromes aren't found twice
+        assert_eq!(1, grid(&["ABA"]).find_word("ABA", true).len());
+    }
+
+    #[test]
+    fn patterns_with_wildcards() {
+        let search = grid(&["MXSXM", "XAXAX", "MXSXM"]);
+        let cross = Pattern::new(&["M.S", ".A.", "M.S"], '.');
+
+        let matches = search.find_pattern(&cross, true);
+        assert_eq!(
+            vec![
+                Match {
+                    position: (0, 0),
+                    direction: Direction::North
+                },
+                Match {
+                    position: (2, 4),
+                    direction: Direction::South
+                },
+            ],
+            matches
+        );
+
+        // the two crosses share their S's
+        assert_eq!(1, search.find_pattern(&cross, false).len());
+    }
+}
@@ -1,7 +1,10 @@
-use std::fs::{ self, File };
-use std::io::{ self, BufRead, BufReader };
-use std::collections::HashSet;
+use std::fs::File;
+use std::io::{ self, BufRead, BufReader, Read };
+use std::cell::OnceCell;
+use std::fmt;
 use crate::{ AdventYear, Year };
+use crate::utils::interval::Interval;
+use crate::utils::parse::{ self, Line, ParseError };
 
 pub fn init() -> Box<dyn AdventYear> {
     let days: Vec<Box<dyn Fn()>> = vec![
@@ -16,29 +19,38 @@ pub fn init() -> Box<dyn AdventYear> {
 }
 
 fn day8() {
-    let trees = parse_day8();
+    let reader = BufReader::new(File::open("./inputs/2022/day8/input").expect("can't open input file"));
+    let trees = parse_day8(reader).unwrap();
 
     println!("Number of visible trees: {}", trees.count_visible());
     println!("Max scene score: {}", trees.highest_scene_score());
 }
 
-fn parse_day8() -> Trees {
+fn parse_day8(reader: impl BufRead) -> Result<Trees, ParseError> {
+    let input = parse::read_input(reader)?;
     let mut trees = Trees { trees: vec![] };
 
-    let reader = BufReader::new(File::open("./inputs/2022/day8/input").expect("can't open input file"));
-    for line_opt in reader.lines() {
-        let line = line_opt.unwrap();
-
-        let tree_row: Vec<u8> = line
+    for line in parse::lines(&input) {
+        let tree_row: Vec<u8> = line.text
             .chars()
-            .map(|character| character.to_digit(10).unwrap() as u8 )
-            .collect();
+            .enumerate()
+            .map(|(i, character)| {
+                character.to_digit(10)
+                    .map(|height| height as u8)
+                    .ok_or_else(|| line.error_at(i, "tree height must be a digit"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if let Some(first) = trees.trees.first() {
+            if first.len() != tree_row.len() {
+                return Err(line.error_at(tree_row.len().min(first.len()), format!("expected a row of {} trees", first.len())));
+            }
+        }
 
-        trees.push(tree_row);
+        trees.trees.push(tree_row);
     }
 
-    trees
-
+    Ok(trees)
 }
 
 struct Trees {
@@ -46,377 +58,510 @@ struct Trees {
 }
 
 impl Trees {
-    pub fn push(&mut self, tree_row: Vec<u8>) {
-        if self.trees.len() == 0 {
-            self.trees.push(tree_row);
-        }
-        else {
-            assert!(self.trees.last().unwrap().len() == tree_row.len(), "new row does not match length of existing rows");
-            self.trees.push(tree_row);
-        }
-    }
-
     pub fn highest_scene_score(&self) -> usize {
-        let mut max_score = 0;
-        if self.trees.len() <= 2 || self.trees.last().unwrap().len() <= 2 {
-            max_score
-        }
-        else {
-            for row in 1..(self.trees.len() - 1) {
-                for column in 1..(self.trees.last().unwrap().len() - 1) {
-                    let scene_score = self.scene_score(row, column);
-                    if scene_score > max_score {
-                        max_score = scene_score;
-                    }
-                }
-            }
-
-            max_score
-        }
+        self.scene_scores()
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(0)
     }
 
-    fn scene_score(&self, row: usize, col: usize) -> usize {
-        let mut visible_left: usize = 0;
-        let mut visible_right: usize = 0;
-        let mut visible_up: usize = 0;
-        let mut visible_down: usize = 0;
-
-        let tree = self.trees[row][col];
-
-        // calculate visible trees to the left
-        for j in (0..col).rev() {
-            if self.trees[row][j] < tree {
-                visible_left += 1;
-            }
-            else {
-                visible_left += 1;
-                break;
-            }
-        }
+    pub fn count_visible(&self) -> usize {
+        self.visibility()
+            .into_iter()
+            .flatten()
+            .filter(|visible| *visible)
+            .count()
+    }
 
-        // calculate visible trees to the right
-        for j in (col + 1)..(self.trees.last().unwrap().len()) {
-            if self.trees[row][j] < tree {
-                visible_right += 1;
-            }
-            else {
-                visible_right += 1;
-                break;
-            }
-        }
+    // whether each tree can be seen from outside the grid
+    pub fn visibility(&self) -> Vec<Vec<bool>> {
+        self.analyse().0
+    }
 
-        // calculate visible trees up
-        for i in (0..row).rev() {
-            if self.trees[i][col] < tree {
-                visible_up += 1;
-            }
-            else {
-                visible_up += 1;
-                break;
-            }
-        }
+    // the product of the viewing distances in each direction for every tree
+    pub fn scene_scores(&self) -> Vec<Vec<usize>> {
+        self.analyse().1
+    }
 
-        // calculate visible trees down
-        for i in (row + 1)..(self.trees.len()) {
-             if self.trees[i][col] < tree {
-                visible_down += 1;
-            }
-            else {
-                visible_down += 1;
-                break;
-            }
-        }
+    // sweeps every row and column in both directions, O(rows * cols)
+    fn analyse(&self) -> (Vec<Vec<bool>>, Vec<Vec<usize>>) {
+        let rows = self.trees.len();
+        let cols = self.trees.first().map_or(0, Vec::len);
 
-        // calculate scene score
-        visible_left * visible_right * visible_up * visible_down 
-    }
+        let mut visible = vec![vec![false; cols]; rows];
+        let mut scores = vec![vec![1; cols]; rows];
 
-    pub fn count_visible(&self) -> usize {
-        let mut num_visible: usize = 0;
-        if self.trees.len() == 0 {
-            num_visible
+        for row in 0..rows {
+            let line: Vec<(usize, usize)> = (0..cols).map(|col| (row, col)).collect();
+            self.sweep(line.iter().copied(), &mut visible, &mut scores);
+            self.sweep(line.iter().rev().copied(), &mut visible, &mut scores);
         }
-        else if self.trees.len() <= 2 || self.trees.last().unwrap().len() <= 2 {
-            self.trees.len() * self.trees.last().unwrap().len()
+        for col in 0..cols {
+            let line: Vec<(usize, usize)> = (0..rows).map(|row| (row, col)).collect();
+            self.sweep(line.iter().copied(), &mut visible, &mut scores);
+            self.sweep(line.iter().rev().copied(), &mut visible, &mut scores);
         }
-        else {
-            for row in 1..(self.trees.len() - 1) {
-                for column in 1..(self.trees.last().unwrap().len() - 1) {
-                    if self.is_visible(row, column) {
-                        num_visible += 1;
-                    }
-                }
-            }
 
-            num_visible + (2 * self.trees.len()) + (2 * (self.trees.last().unwrap().len() - 2))
-        }
+        (visible, scores)
     }
 
-    fn is_visible(&self, row: usize, col: usize) -> bool {
-        let num = self.trees[row][col];
+    /// Looks back along a line of trees from each tree in turn
+    ///
+    /// the stack holds the trees not yet blocked by a later one, in decreasing
+    /// height, so the first one left after popping the shorter trees is the
+    /// one blocking the view. Each tree is pushed and popped at most once
+    fn sweep(&self, line: impl Iterator<Item = (usize, usize)>, visible: &mut [Vec<bool>], scores: &mut [Vec<usize>]) {
+        let mut stack: Vec<(usize, u8)> = vec![];
 
-        let mut blocked = false;
+        for (i, (row, col)) in line.enumerate() {
+            let height = self.trees[row][col];
 
-        for j in 0..self.trees.last().unwrap().len() {
-            // check if visible from the left
-            if j == col {
-                if !blocked {
-                    return true;
-                }
-                blocked = false;
-            }
-            else if self.trees[row][j] >= num {
-                blocked = true
+            while stack.last().is_some_and(|(_, other)| *other < height) {
+                stack.pop();
             }
-        }
-        // check if visible from the right
-        if !blocked {
-            return true;
-        }
-        blocked = false;
 
-        for i in 0..self.trees.len() {
-            // check if visible from the top
-            if i == row {
-                if !blocked {
-                    return true;
+            match stack.last() {
+                Some((blocker, _)) => scores[row][col] *= i - blocker,
+                None => {
+                    // nothing blocks the view back to the edge
+                    visible[row][col] = true;
+                    scores[row][col] *= i;
                 }
-                blocked = false;
-            }
-            else if self.trees[i][col] >= num {
-                blocked = true;
             }
-        }
-        // check if visible from the bottom
-        if !blocked {
-            return true;
-        }
 
-        // blocked on all sides
-        false
+            stack.push((i, height));
+        }
     }
 }
 
 fn day7() {
-    let mut root = parse_day7();
+    let reader = BufReader::new(File::open("./inputs/2022/day7/input").expect("can't open input file"));
+    let fs = parse_day7(reader).unwrap();
 
     let required_space = 30000000;
     let disk_space = 70000000;
-    let current_free = disk_space - root.size();
+    let current_free = disk_space - fs.root().size();
 
     println!("Current free: {}", current_free);
 
-    println!("Part 1 Sum: {}", root.sum_dirs_part_1(100_000));
-    println!("Size of best delete option: {}", root.find_optimal_dir_part_2(required_space, current_free, disk_space));
+    let part1: usize = fs.dirs()
+        .map(|dir| dir.size())
+        .filter(|size| *size <= 100_000)
+        .sum();
+    println!("Part 1 Sum: {}", part1);
+
+    let best_delete = fs.dirs()
+        .map(|dir| dir.size())
+        .filter(|size| size + current_free >= required_space)
+        .min()
+        .unwrap();
+    println!("Size of best delete option: {}", best_delete);
 
 }
 
-fn parse_day7() -> ElfDir {
-    let mut root = ElfDir::new("/".to_string());
+fn parse_day7(reader: impl BufRead) -> Result<ElfFileSystem, ParseError> {
+    let input = parse::read_input(reader)?;
+    ElfFileSystem::replay(&input)
+}
 
-    let reader = BufReader::new(File::open("./inputs/2022/day7/input").expect("can't open input file"));
+enum FSType {
+    Dir(ElfDir),
+    File(ElfFile),
+}
 
-    let mut path: Vec<String> = vec![];
-
-    for line_opt in reader.lines() {
-        
-        let line = line_opt.unwrap();
-        let symbols: Vec<&str> = line.split(' ').collect();
-
-        match symbols[0] {
-            // line contains a command
-            "$" => {
-                match symbols[1] {
-                    "cd" => match symbols[2] {
-                        // return to the root directory
-                        "/" => { path.clear(); },
-                        ".." => { path.pop(); },
-                        dir => { path.push(dir.to_string()); },
-                    },
-                    "ls" => (),
-                    _ => panic!("unexpected command"),
-                }
-            },
-            // line contains a new directory
-            "dir" => { 
-                let directory = FSType::Dir(Box::new(ElfDir::new(symbols[1].to_string())));
-                root.add_fs_item(directory, &path, 0);
-            },
-            // line containts a file
-            file_size => {
-                let size = file_size.parse::<usize>().expect("couldn't parse file size from input");
-                let name = symbols[1].to_string();
-                let file = FSType::File(ElfFile::new(size, name));
-                root.add_fs_item(file, &path, 0);
-            }
-        }
+struct ElfFile {
+    size: usize,
+}
 
-    }
-    root
+struct ElfDir {
+    contents: Vec<usize>,
+    // cleared whenever anything below this directory changes
+    size: OnceCell<usize>,
 }
 
-enum FSType {
-    Dir(Box<ElfDir>),
-    File(ElfFile),
+struct FSNode {
+    name: String,
+    parent: Option<usize>,
+    item: FSType,
 }
 
-impl FSType {
-    pub fn size(&mut self) -> usize {
-        match self {
-            FSType::Dir(item) => item.size(),
-            FSType::File(item) => item.size,
-        }
-    }
+/// An in memory file system, nodes are stored in a flat list and refer to each other by index
+struct ElfFileSystem {
+    nodes: Vec<FSNode>,
+    cwd: usize,
+}
 
-    pub fn name(&self) -> &str {
-        match self {
-            FSType::Dir(item) => &item.name,
-            FSType::File(item) => &item.name,
+#[allow(dead_code)]
+impl ElfFileSystem {
+    const ROOT: usize = 0;
+
+    pub fn new() -> ElfFileSystem {
+        let root = FSNode {
+            name: "/".to_string(),
+            parent: None,
+            item: FSType::Dir(ElfDir { contents: vec![], size: OnceCell::new() }),
+        };
+
+        ElfFileSystem {
+            nodes: vec![root],
+            cwd: ElfFileSystem::ROOT,
         }
     }
 
-}
+    /// Builds a file system from a transcript of `cd` and `ls` commands
+    pub fn replay(transcript: &str) -> Result<ElfFileSystem, ParseError> {
+        let mut fs = ElfFileSystem::new();
+        let mut listing = false;
 
-struct ElfFile {
-    size: usize,
-    name: String,
-}
+        for line in parse::lines(transcript).filter(|line| !line.text.trim().is_empty()) {
+            let words: Vec<Line> = line.words().collect();
+            let symbols: Vec<&str> = words.iter().map(|x| x.text).collect();
 
-impl ElfFile {
-    pub fn new(size: usize, name: String) -> ElfFile {
-        ElfFile {
-            size,
-            name,
+            match symbols[..] {
+                ["$", "cd", path] => {
+                    listing = false;
+                    fs.cd(path).map_err(|e| words[2].error(e))?;
+                },
+                ["$", "ls"] => listing = true,
+                ["$", command, ..] => return Err(words[1].error(format!("unexpected command `{}`", command))),
+                // everything else is output of ls
+                _ if !listing => return Err(line.error_at(0, "output found outside of ls")),
+                ["dir", name] => fs.mkdir(name),
+                [_, name] => {
+                    let size = words[0].parse::<usize>()?;
+                    fs.add_file(name, size);
+                },
+                _ => return Err(line.error_at(0, "expected `dir <name>` or `<size> <name>`")),
+            }
         }
+
+        Ok(fs)
     }
-}
 
-struct ElfDir {
-    size: Option<usize>,
-    name: String,
-    contents: Vec<FSType>,
-}
+    /// Finds a directory from the current directory, or from root if path starts with `/`
+    pub fn resolve(&self, path: &str) -> Result<usize, String> {
+        let mut current = if path.starts_with('/') { ElfFileSystem::ROOT } else { self.cwd };
+
+        for component in path.split('/') {
+            match component {
+                "" | "." => (),
+                // the parent of root is root
+                ".." => current = self.nodes[current].parent.unwrap_or(ElfFileSystem::ROOT),
+                name => {
+                    current = self.child(current, name)
+                        .filter(|child| self.is_dir(*child))
+                        .ok_or_else(|| format!("unknown directory {}", name))?;
+                },
+            }
+        }
+
+        Ok(current)
+    }
 
-impl ElfDir {
-    pub fn new(name: String) -> ElfDir {
-        ElfDir {
-            size: None,
-            name,
-            contents: vec![],
+    pub fn cd(&mut self, path: &str) -> Result<(), String> {
+        self.cwd = self.resolve(path)?;
+        Ok(())
+    }
+
+    // creates a directory in the current directory, if it doesn't exist
+    pub fn mkdir(&mut self, name: &str) {
+        if self.child(self.cwd, name).is_none() {
+            self.insert(name, FSType::Dir(ElfDir { contents: vec![], size: OnceCell::new() }));
         }
     }
 
-    pub fn add_fs_item(&mut self, fs_item: FSType, path: &Vec<String>, index: usize) {
-        if index == path.len() {
-            self.contents.push(fs_item);
+    // creates a file in the current directory, if it doesn't exist
+    pub fn add_file(&mut self, name: &str, size: usize) {
+        if self.child(self.cwd, name).is_none() {
+            self.insert(name, FSType::File(ElfFile { size }));
         }
-        else {
-            if let Some(FSType::Dir(dir)) = self.get_child_item(&path[index]) {
-                dir.add_fs_item(fs_item, path, index + 1);
-            }
-            else {
-                panic!("unkown directory {}", path[index]);
-            }
+    }
+
+    pub fn root(&self) -> FSEntry<'_> {
+        FSEntry { fs: self, id: ElfFileSystem::ROOT }
+    }
+
+    pub fn entry(&self, id: usize) -> FSEntry<'_> {
+        FSEntry { fs: self, id }
+    }
+
+    // iterate over every directory, parents before their contents
+    pub fn dirs(&self) -> Dirs<'_> {
+        Dirs { fs: self, stack: vec![ElfFileSystem::ROOT] }
+    }
+
+    // visits every item depth first, in the order they were found
+    pub fn walk(&self, visitor: &mut impl FSVisitor) {
+        self.walk_from(ElfFileSystem::ROOT, 0, visitor);
+    }
+
+    /// `du` style listing, every directory's size and path with contents before parents
+    pub fn du(&self) -> String {
+        let mut listing = DiskUsage { lines: vec![] };
+        self.walk(&mut listing);
+        listing.lines.join("\n")
+    }
+
+    /// `tree` style dump of the whole file system
+    pub fn tree(&self) -> String {
+        let mut tree = Tree { lines: vec![] };
+        self.walk(&mut tree);
+        tree.lines.join("\n")
+    }
+
+    fn walk_from(&self, id: usize, depth: usize, visitor: &mut impl FSVisitor) {
+        match &self.nodes[id].item {
+            FSType::File(_) => visitor.visit_file(self.entry(id), depth),
+            FSType::Dir(dir) => {
+                visitor.enter_dir(self.entry(id), depth);
+                for child in dir.contents.iter() {
+                    self.walk_from(*child, depth + 1, visitor);
+                }
+                visitor.leave_dir(self.entry(id), depth);
+            },
         }
     }
 
-    pub fn get_child_item(&mut self, name: &str) -> Option<&mut FSType> {
-        for item in self.contents.iter_mut() {
-            if item.name() == name {
-                return Some(item);
-            }
+    fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        match &self.nodes[dir].item {
+            FSType::Dir(dir) => dir.contents.iter()
+                .copied()
+                .find(|child| self.nodes[*child].name == name),
+            FSType::File(_) => None,
         }
-        None
     }
 
-    pub fn sum_dirs_part_1(&mut self, max_size: usize) -> usize {
-        let mut total: usize = 0;
+    fn is_dir(&self, id: usize) -> bool {
+        matches!(self.nodes[id].item, FSType::Dir(_))
+    }
+
+    fn insert(&mut self, name: &str, item: FSType) {
+        let id = self.nodes.len();
+        self.nodes.push(FSNode { name: name.to_string(), parent: Some(self.cwd), item });
 
-        if self.size() <= max_size {
-            total += self.size();
+        if let FSType::Dir(dir) = &mut self.nodes[self.cwd].item {
+            dir.contents.push(id);
         }
-        for item in self.contents.iter_mut() {
-            if let FSType::Dir(dir) = item {
-                total += dir.sum_dirs_part_1(max_size);
+
+        // sizes above the new item are now out of date
+        let mut current = Some(self.cwd);
+        while let Some(id) = current {
+            if let FSType::Dir(dir) = &mut self.nodes[id].item {
+                dir.size.take();
             }
+            current = self.nodes[id].parent;
         }
-        total
+    }
+}
+
+/// A reference to a single item of an ElfFileSystem
+#[derive(Clone, Copy)]
+struct FSEntry<'a> {
+    fs: &'a ElfFileSystem,
+    id: usize,
+}
+
+#[allow(dead_code)]
+impl<'a> FSEntry<'a> {
+    pub fn name(&self) -> &'a str {
+        &self.fs.nodes[self.id].name
     }
 
-    pub fn find_optimal_dir_part_2(&mut self, required_space: usize, current_free: usize, current_best: usize) -> usize {
-        let remove_self = self.size() + current_free;
+    pub fn is_dir(&self) -> bool {
+        self.fs.is_dir(self.id)
+    }
 
-        let mut best_option = current_best;
-        
-        if remove_self >= required_space && self.size() < current_best {
-            best_option = self.size();
+    // total size of everything within this item, computed once and cached
+    pub fn size(&self) -> usize {
+        match &self.fs.nodes[self.id].item {
+            FSType::File(file) => file.size,
+            FSType::Dir(dir) => *dir.size.get_or_init(|| {
+                dir.contents.iter()
+                    .map(|child| self.fs.entry(*child).size())
+                    .sum()
+            }),
         }
+    }
 
-        for item in self.contents.iter_mut() {
-            if let FSType::Dir(dir) = item {
-                best_option = dir.find_optimal_dir_part_2(required_space, current_free, best_option);
-            }
+    pub fn path(&self) -> String {
+        let mut names = vec![];
+        let mut current = self.id;
+        while let Some(parent) = self.fs.nodes[current].parent {
+            names.push(self.fs.nodes[current].name.as_str());
+            current = parent;
         }
+        names.reverse();
 
-        best_option
+        format!("/{}", names.join("/"))
     }
+}
 
-    pub fn size(&mut self) -> usize {
-        match self.size {
-            Some(size) => size,
-            None => {
-                let size = self.contents.iter_mut().map(|fs_item| fs_item.size()).sum();
-                self.size = Some(size);
-                size
-            },
+struct Dirs<'a> {
+    fs: &'a ElfFileSystem,
+    stack: Vec<usize>,
+}
+
+impl<'a> Iterator for Dirs<'a> {
+    type Item = FSEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        if let FSType::Dir(dir) = &self.fs.nodes[id].item {
+            // push in reverse so contents come out in the order they were found
+            self.stack.extend(dir.contents.iter().rev().filter(|child| self.fs.is_dir(**child)));
         }
+
+        Some(self.fs.entry(id))
+    }
+}
+
+#[allow(dead_code)]
+trait FSVisitor {
+    fn enter_dir(&mut self, _dir: FSEntry, _depth: usize) {}
+    fn leave_dir(&mut self, _dir: FSEntry, _depth: usize) {}
+    fn visit_file(&mut self, _file: FSEntry, _depth: usize) {}
+}
+
+#[allow(dead_code)]
+struct DiskUsage {
+    lines: Vec<String>,
+}
+
+impl FSVisitor for DiskUsage {
+    fn leave_dir(&mut self, dir: FSEntry, _depth: usize) {
+        self.lines.push(format!("{}\t{}", dir.size(), dir.path()));
+    }
+}
+
+#[allow(dead_code)]
+struct Tree {
+    lines: Vec<String>,
+}
+
+impl FSVisitor for Tree {
+    fn enter_dir(&mut self, dir: FSEntry, depth: usize) {
+        self.lines.push(format!("{}- {} (dir)", "  ".repeat(depth), dir.name()));
+    }
+
+    fn visit_file(&mut self, file: FSEntry, depth: usize) {
+        self.lines.push(format!("{}- {} (file, size={})", "  ".repeat(depth), file.name(), file.size()));
     }
 }
 
 fn day6() {
-    let input = fs::read_to_string("./inputs/2022/day6/input").unwrap();
+    let input = || File::open("./inputs/2022/day6/input").expect("can't open input file");
 
-    let packet_marker = find_marker(&input, 4).unwrap();
-    let message_marker = find_marker(&input, 14).unwrap();
+    let packet_marker = find_marker(input(), 4).unwrap().expect("no packet marker found");
+    let message_marker = find_marker(input(), 14).unwrap().expect("no message marker found");
 
     println!("Packet marker: {}", packet_marker);
     println!("Message marker: {}", message_marker);
 }
 
-fn find_marker(input: &str, marker_length: usize) -> Option<usize> {
-    assert!(input.is_ascii());
-    assert!(input.len() >= marker_length);
+// number of bytes read when the first marker is complete
+fn find_marker(reader: impl Read, marker_length: usize) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(marker_length);
+    let mut found = None;
+
+    read_chunks(reader, |chunk| {
+        found = chunk.iter().find_map(|byte| detector.push(*byte));
+        found.is_none()
+    })?;
+
+    Ok(found)
+}
+
+// every position where a marker is complete, markers may overlap
+#[allow(dead_code)]
+fn find_all_markers(reader: impl Read, marker_length: usize) -> io::Result<Vec<usize>> {
+    let mut detector = MarkerDetector::new(marker_length);
+    let mut markers = vec![];
+
+    read_chunks(reader, |chunk| {
+        markers.extend(chunk.iter().filter_map(|byte| detector.push(*byte)));
+        true
+    })?;
+
+    Ok(markers)
+}
 
+// streams the reader a chunk at a time until it ends or on_chunk returns false
+fn read_chunks(mut reader: impl Read, mut on_chunk: impl FnMut(&[u8]) -> bool) -> io::Result<()> {
+    let mut buffer = [0; 8192];
 
-    let ascii = input.as_bytes().to_vec();
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
 
-    let slice_length = marker_length;
-    for i in 0..(ascii.len() - slice_length) {
-        if is_slice_unique(&ascii[i..(i+slice_length)]) {
-            return Some(i + slice_length);
+        if !on_chunk(&buffer[..read]) {
+            return Ok(());
         }
     }
-    None
+}
 
+/// Tracks whether the last `length` bytes of a stream are all different
+///
+/// keeps a count of each byte in the window, so each byte is O(1)
+struct MarkerDetector {
+    // the window, as a ring buffer indexed by position
+    window: Vec<u8>,
+    counts: [usize; 256],
+    // bytes in the window appearing more than once
+    repeated: usize,
+    position: usize,
 }
 
-fn is_slice_unique(slice: &[u8]) -> bool {
-    for (i, _) in slice.iter().enumerate() {
-        for j in (i + 1)..slice.len() {
-            if slice[i] == slice[j] {
-                return false;
+impl MarkerDetector {
+    pub fn new(length: usize) -> MarkerDetector {
+        assert!(length > 0, "markers must be at least one byte long");
+
+        MarkerDetector {
+            window: vec![0; length],
+            counts: [0; 256],
+            repeated: 0,
+            position: 0,
+        }
+    }
+
+    // adds a byte, returning the number of bytes read if it completes a marker
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        let length = self.window.len();
+        let slot = self.position % length;
+
+        // drop the byte leaving the window
+        if self.position >= length {
+            let old = self.window[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.repeated -= 1;
             }
         }
+
+        self.window[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
+        self.position += 1;
+
+        (self.position >= length && self.repeated == 0).then_some(self.position)
     }
-     true
 }
 
 fn day5() {
-    let mut ship1 = parse_day5();
-    let mut ship2 = ship1.clone();
+    let reader = BufReader::new(File::open("./inputs/2022/day5/input").expect("can't read input file"));
+    let ship = parse_day5(reader).unwrap();
+
+    let mut ship1 = ship.clone();
+    let mut ship2 = ship.clone();
 
-    ship1.execute_part1_movements();
-    ship2.execute_part2_movements();
+    ship1.execute(Crane::OneAtATime).unwrap();
+    ship2.execute(Crane::AllAtOnce).unwrap();
 
     let top1 = ship1.read_top();
     let top2 = ship2.read_top();
@@ -425,73 +570,96 @@ fn day5() {
     println!("Part 2 top of stacks: {}", top2);
 }
 
-fn parse_day5() -> Ship {
-    let reader = BufReader::new(File::open("./inputs/2022/day5/input").expect("can't read input file"));
-    let lines_iter = reader.lines();
+fn parse_day5(reader: impl BufRead) -> Result<Ship, ParseError> {
+    let input = parse::read_input(reader)?;
+    let sections = parse::sections(&input);
 
-    let mut cargo_hold: Vec<Vec<char>> = vec![];
-    let mut movements: Vec<Movement> = vec![];
-    
-    for text_line in lines_iter {
-        let line = text_line.unwrap();
-        // skip the empty line
-        if line.is_empty() || line.trim().starts_with('1') {
-            continue;
-        }
-        // parse cargo lines
-        else if (line.starts_with('[') || line.starts_with(' ')) && !line.chars().nth(1).unwrap().is_numeric() {
-            // initialize cargo held stacks if needed
-            if cargo_hold.is_empty() {
-                let cargo_width = (line.len() + 1) / 4;
-                for _ in 0..cargo_width {
-                    cargo_hold.push(vec![]);
-                }
-            }
+    let (drawing, moves) = match &sections[..] {
+        [drawing] => (drawing, &vec![]),
+        [drawing, moves] => (drawing, moves),
+        _ => return Err(ParseError::new(1, 1, "expected a drawing and a list of moves separated by a blank line")),
+    };
 
-            parse_cargo_line(&mut cargo_hold, &line);
+    // the last line of the drawing labels the stacks
+    let (labels, cargo_lines) = drawing.split_last().unwrap();
+    let num_stacks = labels.tokens::<usize>()?.len();
+    let mut cargo_hold: Vec<Vec<char>> = vec![vec![]; num_stacks];
 
-        }
-        // parse the move instructions
-        else if line.starts_with('m') {
-            let line_components: Vec<&str> = line.split(' ')
-                .collect();
-            movements.push( Movement {
-                num: line_components[1].parse::<usize>().unwrap(),
-                from: line_components[3].parse::<usize>().unwrap(),
-                to: line_components[5].parse::<usize>().unwrap(),
-            });
-        }
-        else {
-            panic!("unexpected input: {}", line);
-        }
+    for line in cargo_lines {
+        parse_cargo_line(&mut cargo_hold, line)?;
     }
 
     for stack in cargo_hold.iter_mut() {
         stack.reverse();
     }
 
-    
+    // parse the move instructions
+    let movements = moves.iter()
+        .map(|line| {
+            match line.integers::<usize>()?[..] {
+                [num, from, to] => Ok(Movement { num, from, to }),
+                _ => Err(line.error_at(0, "expected `move <num> from <stack> to <stack>`")),
+            }
+        })
+        .collect::<Result<_, _>>()?;
 
-    Ship {
+    Ok(Ship {
         hold: cargo_hold,
         movements,
+    })
+}
+
+// crates are drawn as `[X]`, with a space between each stack
+fn parse_cargo_line(cargo_hold: &mut [Vec<char>], line: &Line) -> Result<(), ParseError> {
+    for (i, current_crate) in line.text.char_indices() {
+        if i % 4 == 1 && current_crate.is_alphabetic() {
+            cargo_hold.get_mut(i / 4)
+                .ok_or_else(|| line.error_at(i, "crate is outside of the labelled stacks"))?
+                .push(current_crate);
+        }
+        else if !matches!(current_crate, '[' | ']' | ' ') {
+            return Err(line.error_at(i, format!("unexpected character `{}`", current_crate)));
+        }
     }
+
+    Ok(())
 }
 
-fn parse_cargo_line(cargo_hold: &mut Vec<Vec<char>>, line: &str) {
+/// How many crates the crane lifts in a single move
+#[derive(Debug, Clone, Copy)]
+enum Crane {
+    // reverses the order of the moved crates
+    OneAtATime,
+    // keeps the order of the moved crates
+    AllAtOnce,
+    // lifts up to the given number of crates, keeping their order
+    #[allow(dead_code)]
+    Batch(usize),
+}
 
-    let mut chars = line.chars();
-    for i in 0..line.len() {
-        let current_crate = chars.next().unwrap();
-        if i == 1 || (i >= 4 && ((i - 1) % 4) == 0) {
-            if current_crate.is_alphabetic() {
-                let index = i / 4;
-                cargo_hold[index].push(current_crate);
-            }
+impl Crane {
+    fn batch_size(&self, remaining: usize) -> usize {
+        match self {
+            Crane::OneAtATime => 1,
+            Crane::AllAtOnce => remaining,
+            Crane::Batch(size) => (*size).clamp(1, remaining),
         }
     }
+}
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MoveError {
+    UnknownStack { step: usize, stack: usize },
+    EmptyStack { step: usize, stack: usize },
+}
 
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::UnknownStack { step, stack } => write!(f, "move {} uses unknown stack {}", step, stack),
+            MoveError::EmptyStack { step, stack } => write!(f, "move {} takes too many crates from stack {}", step, stack),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -501,40 +669,88 @@ struct Ship {
 }
 
 impl Ship {
-    pub fn execute_part1_movements(&mut self) {
-        for movement in self.movements.iter() {
-            let from = movement.from - 1;
-            let to = movement.to - 1;
-            for _ in 0..movement.num {
-                let current_crate = self.hold[from].pop().unwrap();
-                self.hold[to].push(current_crate);
-            }
+    pub fn execute(&mut self, crane: Crane) -> Result<(), MoveError> {
+        self.execute_traced(crane, |_, _| ())
+    }
+
+    // executes every movement, calling on_step with each movement and the resulting ship
+    pub fn execute_traced(&mut self, crane: Crane, mut on_step: impl FnMut(&Movement, &Ship)) -> Result<(), MoveError> {
+        let movements = std::mem::take(&mut self.movements);
 
+        let mut result = Ok(());
+        for (step, movement) in movements.iter().enumerate() {
+            result = self.apply(step + 1, movement, crane);
+            if result.is_err() {
+                break;
+            }
+            on_step(movement, self);
         }
+
+        self.movements = movements;
+        result
     }
 
-    pub fn execute_part2_movements(&mut self) {
-        for movement in self.movements.iter() {
-            let from = movement.from - 1;
-            let to = movement.to - 1;
+    // executes every movement, rendering the ship after each one
+    #[allow(dead_code)]
+    pub fn trace(&mut self, crane: Crane) -> Result<Vec<String>, MoveError> {
+        let mut steps = vec![];
+        self.execute_traced(crane, |movement, ship| steps.push(format!("{}\n{}", movement, ship.render())))?;
+        Ok(steps)
+    }
 
-            let mut small_stack: Vec<char> = vec![];
-            for _ in 0..movement.num {
-                let current_crate = self.hold[from].pop().unwrap();
-                small_stack.push(current_crate);
+    fn apply(&mut self, step: usize, movement: &Movement, crane: Crane) -> Result<(), MoveError> {
+        // check the whole move before changing anything
+        for stack in [movement.from, movement.to] {
+            if stack == 0 || stack > self.hold.len() {
+                return Err(MoveError::UnknownStack { step, stack });
             }
+        }
+        let from = movement.from - 1;
+        let to = movement.to - 1;
+        if self.hold[from].len() < movement.num {
+            return Err(MoveError::EmptyStack { step, stack: movement.from });
+        }
 
-            for current_crate in small_stack.into_iter().rev() {
-                self.hold[to].push(current_crate);
-            }
+        let mut remaining = movement.num;
+        while remaining > 0 {
+            let batch = crane.batch_size(remaining);
+            let split = self.hold[from].len() - batch;
+            let lifted = self.hold[from].split_off(split);
+            self.hold[to].extend(lifted);
+            remaining -= batch;
         }
+
+        Ok(())
+    }
+
+    // draws the ship in the same format as the input
+    pub fn render(&self) -> String {
+        let height = self.hold.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut lines: Vec<String> = (0..height).rev()
+            .map(|level| {
+                self.hold.iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(current_crate) => format!("[{}]", current_crate),
+                        None => "   ".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+
+        let labels = (1..=self.hold.len())
+            .map(|label| format!(" {} ", label))
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(labels);
+
+        lines.join("\n")
     }
 
     pub fn read_top(&self) -> String {
         self.hold.iter()
-            .map(|item| item.last())
-            .filter(|item| item.is_some())
-            .map(|item| item.unwrap())
+            .filter_map(|item| item.last())
             .collect()
     }
 }
@@ -546,31 +762,15 @@ struct Movement {
     pub to: usize,
 }
 
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.num, self.from, self.to)
+    }
+}
+
 fn day4() {
     let reader = BufReader::new(File::open("./inputs/2022/day4/input").expect("can't read 2022 day3 input"));
-    let elf_pairs: Vec<ElfPair> = reader.lines()
-        .map(|pair| {
-            let line = pair.expect("can't read 2022 day 4 input");
-            let elf_ranges: Vec<&str> = line
-                .split(',')
-                .collect();
-            assert!(elf_ranges.len() == 2, "unexpected input format");
-
-            let mut elf_pair: Vec<Vec<usize>> = elf_ranges.iter()
-                .map(|elf_range| { 
-                    let range: Vec<usize> = elf_range.split('-')
-                        .map(|range_end| { range_end.parse::<usize>().expect("unable to parse int from range") })
-                        .collect();
-                    range
-                })
-                .collect();
-
-            assert!(elf_pair.len() == 2, "invalid number of elves parsed from an elf pair");
-            let elf2 = elf_pair.pop().unwrap();
-            let elf1 = elf_pair.pop().unwrap();
-            ElfPair::new(elf1, elf2)
-        })
-        .collect();
+    let elf_pairs = parse_day4(reader).unwrap();
 
     let num_fully_overlapping = elf_pairs.iter()
         .filter(|pair| pair.completely_overlapping())
@@ -584,17 +784,31 @@ fn day4() {
     println!("Overlapping pairs: {}", num_overlapping);
 }
 
+fn parse_day4(reader: impl BufRead) -> Result<Vec<ElfPair>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::lines(&input)
+        .map(|line| {
+            match line.integers::<usize>()?[..] {
+                // assignments are inclusive of both ends
+                [start1, end1, start2, end2] => Ok(ElfPair::new(
+                    Interval::new(start1, end1 + 1),
+                    Interval::new(start2, end2 + 1),
+                )),
+                _ => Err(line.error_at(0, "expected two ranges formatted `a-b,c-d`")),
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 struct ElfPair {
-    elf1: Vec<usize>,
-    elf2: Vec<usize>,
+    elf1: Interval<usize>,
+    elf2: Interval<usize>,
 }
 
 impl ElfPair {
-    fn new(elf1: Vec<usize>, elf2: Vec<usize>) -> ElfPair {
-        assert!(elf1.len() == 2, "invalid range assigned to elf, likely parsing error");
-        assert!(elf2.len() == 2, "invalid range assigned to elf, likely parsing error");
-
+    fn new(elf1: Interval<usize>, elf2: Interval<usize>) -> ElfPair {
         ElfPair {
             elf1,
             elf2,
@@ -603,283 +817,414 @@ impl ElfPair {
 
     // determine whether either range contains the other
     fn completely_overlapping(&self) -> bool {
-        (self.elf1[0] <= self.elf2[0] && self.elf1[1] >= self.elf2[1]) ||
-            (self.elf2[0] <= self.elf1[0] && self.elf2[1] >= self.elf1[1])
+        self.elf1.contains_interval(&self.elf2) || self.elf2.contains_interval(&self.elf1)
     }
 
     // determines whether either range overlaps at all
     fn overlapping(&self) -> bool {
-        (self.elf1[0] >= self.elf2[0] && self.elf1[0] <= self.elf2[1]) || 
-            (self.elf1[1] >= self.elf2[0] && self.elf1[1] <= self.elf2[1]) ||
-            (self.elf2[0] >= self.elf1[0] && self.elf2[0] <= self.elf1[1]) ||
-            (self.elf2[1] >= self.elf1[0] && self.elf2[1] <= self.elf1[1])
-
+        self.elf1.overlaps(&self.elf2)
     }
 }
 
 fn day3() {
     let reader = BufReader::new(File::open("./inputs/2022/day3/input").expect("can't read 2022 day3 input"));
-    let mut rucksacks: Vec<Rucksack> = reader.lines()
-        .map(|line| { Rucksack::new(line.expect("io error on line")) })
-        .collect();
+    let rucksacks = parse_day3(reader).unwrap();
 
-    let rucksack_priority_sum: usize = rucksacks.iter_mut()
-        .map(|rucksack| { rucksack.common_priority() })
+    let rucksack_priority_sum: u32 = rucksacks.iter()
+        .map(|rucksack| rucksack.shared_items().priority_sum())
         .sum();
     println!("Sum of priorities: {}", rucksack_priority_sum);
 
 
     // check that there are only complete groups
     let group_size = 3;
-    assert!(rucksacks.len() % group_size == 0, "Incomplete groups found in input");
-
-    let mut badges: Vec<char> = vec![];
-    for i in (0..rucksacks.len()).step_by(group_size) {
-        let group_intersection = &(&rucksacks[i].item_set & &rucksacks[i + 1].item_set) & &rucksacks[i + 2].item_set;
-        assert!(group_intersection.len() == 1, "multiple badge options found for a single group");
-        badges.push(group_intersection.into_iter().next().unwrap());
-    }
+    assert!(rucksacks.len().is_multiple_of(group_size), "Incomplete groups found in input");
 
-    let group_priority_sum: usize = badges.iter()
-        .map(|badge| Rucksack::compute_item_priority(*badge))
+    let group_priority_sum: u32 = group_badges(&rucksacks, group_size).iter()
+        .map(|badges| {
+            assert!(badges.len() == 1, "multiple badge options found for a single group");
+            badges.priority_sum()
+        })
         .sum();
 
     println!("Sum of badge priorities: {}", group_priority_sum);
 }
 
+fn parse_day3(reader: impl BufRead) -> Result<Vec<Rucksack>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::lines(&input)
+        .map(|line| {
+            if let Some(i) = line.text.find(|c: char| !c.is_ascii_alphabetic()) {
+                return Err(line.error_at(i, "items must be ascii letters"));
+            }
+            if !line.text.len().is_multiple_of(2) {
+                return Err(line.error_at(line.text.len(), "compartments must hold the same number of items"));
+            }
+
+            Ok(Rucksack::new(line.text))
+        })
+        .collect()
+}
+
+// items carried by every rucksack of each group
+fn group_badges(rucksacks: &[Rucksack], group_size: usize) -> Vec<ItemSet> {
+    rucksacks.chunks(group_size)
+        .map(|group| {
+            group.iter()
+                .fold(ItemSet::all(), |badges, rucksack| badges & rucksack.items)
+        })
+        .collect()
+}
+
 struct Rucksack {
-    total: Box<String>,
-    pub item_set: HashSet<char>,
-    midpoint: usize,
-    common_item: Option<char>,
-    common_priority: Option<usize>,
+    items: ItemSet,
+    compartments: [ItemSet; 2],
 }
 
 impl Rucksack {
-    pub fn new(inventory: String) -> Rucksack {
+    pub fn new(inventory: &str) -> Rucksack {
         // check function invariants
-        assert!(inventory.len() % 2 == 0);
-        assert!(inventory.is_ascii());
-        assert!(inventory.chars().all(char::is_alphabetic));
-
-        let midpoint = inventory.len() / 2;
+        assert!(inventory.len().is_multiple_of(2));
 
-        let item_set = HashSet::from_iter(inventory.chars());
+        let (compartment1, compartment2) = inventory.split_at(inventory.len() / 2);
+        let compartments = [ItemSet::from_items(compartment1), ItemSet::from_items(compartment2)];
 
         Rucksack {
-            total: Box::new(inventory),
-            item_set,
-            midpoint,
-            common_item: None,
-            common_priority: None,
+            items: compartments[0] | compartments[1],
+            compartments,
         }
     }
 
-    pub fn common_item(&mut self) -> char {
-        let compartment1 = &self.total[..self.midpoint];
-        let compartment2 = &self.total[self.midpoint..];
+    // items found in both compartments
+    pub fn shared_items(&self) -> ItemSet {
+        self.compartments[0] & self.compartments[1]
+    }
+}
 
-        let mut common_item = None;
-        for item in compartment1.chars() {
-            if compartment2.contains(item) {
-                match common_item {
-                    Some(x) => assert!(x == item, "multiple matching items in Rucksack"),
-                    None => common_item = Some(item),
-                }
-            }
+/// A set of items, with bit n set for the item with priority n
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    // every item from a to Z
+    pub fn all() -> ItemSet {
+        ItemSet(((1 << 52) - 1) << 1)
+    }
+
+    pub fn from_items(items: &str) -> ItemSet {
+        items.chars().fold(ItemSet::default(), |mut set, item| {
+            set.insert(item);
+            set
+        })
+    }
+
+    // a to z have priorities 1 to 26, A to Z have 27 to 52
+    pub fn priority(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+
+    pub fn item(priority: u32) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority - 1),
+            27..=52 => char::from_u32('A' as u32 + priority - 27),
+            _ => None,
         }
+    }
 
-        if let None = common_item { panic!("Unable to find matching item in Rucksack") }
+    pub fn insert(&mut self, item: char) {
+        let priority = ItemSet::priority(item).expect("items must be ascii letters");
+        self.0 |= 1 << priority;
+    }
 
-        self.common_item = common_item;
-        common_item.unwrap()
+    #[allow(dead_code)]
+    pub fn contains(&self, item: char) -> bool {
+        ItemSet::priority(item).is_some_and(|priority| self.0 & (1 << priority) != 0)
     }
 
-    pub fn common_priority(&mut self) -> usize {
-        let common_item = match self.common_item {
-            None => self.common_item(),
-            Some(item) => item,
-        };
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
 
-        self.common_priority = Some(Rucksack::compute_item_priority(common_item));
-        self.common_priority.unwrap()
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
     }
 
-    fn compute_item_priority(item: char) -> usize {
-        let upper_case_offset = 38;
-        let lower_case_offset = 96;
+    // priorities of every item in the set, lowest first
+    pub fn priorities(&self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
 
-        let mut buff = [0; 4];
-        item.encode_utf8(&mut buff);
-        if item.is_uppercase() {
-            usize::from(buff[0]) - upper_case_offset
-        }
-        else {
-            usize::from(buff[0]) - lower_case_offset
-        }
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let priority = bits.trailing_zeros();
+            // clear the lowest set bit
+            bits &= bits - 1;
+            Some(priority)
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn items(&self) -> impl Iterator<Item = char> {
+        self.priorities().filter_map(ItemSet::item)
+    }
+
+    pub fn priority_sum(&self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+impl std::ops::BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: ItemSet) -> ItemSet {
+        ItemSet(self.0 & rhs.0)
+    }
+}
+
+impl std::ops::BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: ItemSet) -> ItemSet {
+        ItemSet(self.0 | rhs.0)
     }
 }
 
 fn day2() {
     let reader = BufReader::new(File::open("./inputs/2022/day2/input").expect("unable to read input file for 2022 day2"));
-    
-    let rounds: Vec<(RPSRound, RPSRound)> = reader.lines()
-        .map(|round| {
-            let line = round.expect("unable to read line");
-            let mut char_iter = line.chars();
-
-            // parse opponent move
-            let opponent = match char_iter.next().unwrap() {
-                'A' => RPS::Rock,
-                'B' => RPS::Paper,
-                'C' => RPS::Scissors,
-                _ => panic!("unexpected first symbol"),
-            };
+    let input = parse::read_input(reader).unwrap();
+    let game = RPSGame::classic();
+
+    // the second column is either my move or how the round should end
+    let moves = parse_day2(input.as_bytes(), &game, "ABC", "XYZ", Column::Move).unwrap();
+    let outcomes = parse_day2(input.as_bytes(), &game, "ABC", "XYZ", Column::Outcome).unwrap();
+    let (_, part1) = game.play_guide(&moves, Column::Move);
+    let (_, part2) = game.play_guide(&outcomes, Column::Outcome);
+
+    println!("Part 1 total score: {}", part1.me);
+    println!("Part 2 total score: {}", part2.me);
+}
+
+
+// parses each round into the index of the symbol in each column, checking
+// the index is one of the game's moves, or an outcome for the second column
+fn parse_day2(reader: impl BufRead, game: &RPSGame, opponent_symbols: &str, my_symbols: &str, column: Column) -> Result<Vec<(usize, usize)>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    let symbol = |line: &Line, index: usize, symbols: &str, count: usize, kind: &str| {
+        let c = line.text[index..].chars().next()
+            .ok_or_else(|| line.error_at(index, format!("expected one of `{}`", symbols)))?;
+        let value = symbols.chars().position(|x| x == c)
+            .ok_or_else(|| line.error_at(index, format!("expected one of `{}`", symbols)))?;
+
+        if value >= count {
+            return Err(line.error_at(index, format!("`{}` is past the {} {}", c, count, kind)));
+        }
+        Ok(value)
+    };
+
+    let (count, kind) = match column {
+        Column::Move => (game.moves.len(), "moves"),
+        Column::Outcome => (Outcome::ALL.len(), "outcomes"),
+    };
+
+    parse::lines(&input)
+        .map(|line| {
+            let opponent = symbol(&line, 0, opponent_symbols, game.moves.len(), "moves")?;
 
             // skip whitespace character
-            char_iter.next().unwrap();
-
-            // parse my move for question 1
-            let my_char = char_iter.next().unwrap();
-            let me_part1 = match my_char {
-                'X' => RPS::Rock,
-                'Y' => RPS::Paper,
-                'Z' => RPS::Scissors,
-                _ => panic!("unexpected third symbol"),
-            };
-
-
-            // parse my move for question 2
-            let me_part2 =  match opponent {
-                RPS::Rock => {
-                    match my_char {
-                        'X' => RPS::Scissors,
-                        'Y' => RPS::Rock,
-                        'Z' => RPS::Paper,
-                        _ => panic!("unexpected third symbol"),
-                    }
-                },
-                RPS::Paper => {
-                    match my_char {
-                        'X' => RPS::Rock,
-                        'Y' => RPS::Paper,
-                        'Z' => RPS::Scissors,
-                        _ => panic!("unexpected third symbol"),
-                    }
-                },
-                RPS::Scissors => {
-                    match my_char {
-                        'X' => RPS::Paper,
-                        'Y' => RPS::Scissors,
-                        'Z' => RPS::Rock,
-                        _ => panic!("unexpected third symbol"),
-                    } 
-                }
-            };
+            if line.text.get(1..2) != Some(" ") {
+                return Err(line.error_at(1, "expected a space"));
+            }
 
-            // return a tuple formatted (part 1 round, part 2 round)
-            (RPSRound::new(opponent, me_part1), RPSRound::new(opponent, me_part2))
+            Ok((opponent, symbol(&line, 2, my_symbols, count, kind)?))
         })
-        .collect();
+        .collect()
+}
 
-    let (mut total_part1, mut total_part2) = (0, 0);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
 
-    // sum up rounds for parts 1 and 2
-    for round in rounds {
-        total_part1 += round.0.score().1;
-        total_part2 += round.1.score().1;
-    }
+impl Outcome {
+    const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
 
-    println!("Part 1 total score: {}", total_part1);
-    println!("Part 2 total score: {}", total_part2);
+    fn reverse(&self) -> Outcome {
+        match self {
+            Outcome::Loss => Outcome::Win,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Win => Outcome::Loss,
+        }
+    }
 }
 
+// what the second column of the strategy guide means
+#[derive(Debug, Clone, Copy)]
+enum Column {
+    // the move to play, in the order of the game's moves
+    Move,
+    // loss, draw or win
+    Outcome,
+}
 
-#[derive(Copy, Clone)]
-enum RPS {
-    Rock,
-    Paper,
-    Scissors,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Score {
+    opponent: i32,
+    me: i32,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct RPSRound {
-    opponent: RPS,
-    me: RPS,
+    opponent: usize,
+    me: usize,
+    // from my side of the round
+    outcome: Outcome,
+    score: Score,
 }
 
-impl RPSRound {
-    pub fn new(opponent: RPS, me: RPS) -> RPSRound {
-        RPSRound {
-            opponent,
-            me,
+/// A game of moves where some moves beat others
+///
+/// which move beats which is given as (winner, loser) pairs, moves without a
+/// rule between them draw. Every move has to beat some move and lose to
+/// another, so there's always a move to reach any outcome
+struct RPSGame {
+    moves: Vec<&'static str>,
+    move_scores: Vec<i32>,
+    // loss, draw, win
+    outcome_scores: [i32; 3],
+    // beats[a][b] when a beats b
+    beats: Vec<Vec<bool>>,
+}
+
+impl RPSGame {
+    pub fn new(moves: Vec<&'static str>, move_scores: Vec<i32>, outcome_scores: [i32; 3], rules: &[(usize, usize)]) -> Result<RPSGame, String> {
+        if moves.len() != move_scores.len() {
+            return Err(format!("{} moves but {} move scores", moves.len(), move_scores.len()));
+        }
+
+        let mut beats = vec![vec![false; moves.len()]; moves.len()];
+        for (winner, loser) in rules.iter().copied() {
+            if winner.max(loser) >= moves.len() {
+                return Err(format!("rule {} beats {} refers to a move that doesn't exist", winner, loser));
+            }
+            if winner == loser || beats[loser][winner] {
+                return Err(format!("{} and {} can't both beat each other", moves[winner], moves[loser]));
+            }
+            beats[winner][loser] = true;
+        }
+
+        for (i, name) in moves.iter().enumerate() {
+            if !beats[i].iter().any(|x| *x) {
+                return Err(format!("{} doesn't beat any move", name));
+            }
+            if !beats.iter().any(|x| x[i]) {
+                return Err(format!("{} can't be beaten", name));
+            }
         }
+
+        Ok(RPSGame {
+            moves,
+            move_scores,
+            outcome_scores,
+            beats,
+        })
     }
 
-    pub fn score(&self) -> (i32, i32) {
-        let (mut opponent_score, mut my_score) = RPSRound::outcome_score(self.opponent, self.me);
-        opponent_score += RPSRound::symbol_score(self.opponent);
-        my_score += RPSRound::symbol_score(self.me);
+    /// A game where each move beats the moves an odd number of places before it
+    ///
+    /// only fair with an odd number of moves, where every pair of different
+    /// moves has a winner, so Rock Paper Scissors Spock Lizard works in that order
+    pub fn cyclic(moves: Vec<&'static str>, move_scores: Vec<i32>, outcome_scores: [i32; 3]) -> Result<RPSGame, String> {
+        let len = moves.len();
+        let rules: Vec<(usize, usize)> = (0..len)
+            .flat_map(|winner| (1..len).step_by(2).map(move |distance| (winner, (winner + len - distance) % len)))
+            .collect();
 
-        (opponent_score, my_score)
+        RPSGame::new(moves, move_scores, outcome_scores, &rules)
+    }
 
+    pub fn classic() -> RPSGame {
+        RPSGame::cyclic(vec!["Rock", "Paper", "Scissors"], vec![1, 2, 3], [0, 3, 6]).unwrap()
     }
 
-    fn symbol_score(symbol: RPS) -> i32 {
-        match symbol {
-            RPS::Rock => 1,
-            RPS::Paper => 2,
-            RPS::Scissors => 3,
+    pub fn outcome(&self, me: usize, opponent: usize) -> Outcome {
+        if self.beats[me][opponent] {
+            Outcome::Win
+        }
+        else if self.beats[opponent][me] {
+            Outcome::Loss
+        }
+        else {
+            Outcome::Draw
         }
     }
 
-    fn outcome_score(player1: RPS, player2: RPS) -> (i32, i32) {
-        match player1 {
-            RPS::Rock => {
-                match player2 {
-                    RPS::Rock => (3, 3),  
-                    RPS::Paper => (0, 6), 
-                    RPS::Scissors => (6, 0),
-                }
-            },
-            RPS::Paper => {
-                match player2 {
-                    RPS::Rock => (6, 0),
-                    RPS::Paper => (3, 3),
-                    RPS::Scissors => (0, 6),
-                }
+    // the first move that ends the round with outcome against opponent
+    pub fn move_for(&self, opponent: usize, outcome: Outcome) -> usize {
+        if outcome == Outcome::Draw {
+            return opponent;
+        }
+
+        (0..self.moves.len())
+            .find(|me| self.outcome(*me, opponent) == outcome)
+            .expect("every move beats one move and loses to another")
+    }
+
+    pub fn play(&self, opponent: usize, me: usize) -> RPSRound {
+        let outcome = self.outcome(me, opponent);
+        let outcome_score = |outcome: Outcome| self.outcome_scores[outcome as usize];
+
+        RPSRound {
+            opponent,
+            me,
+            outcome,
+            score: Score {
+                opponent: self.move_scores[opponent] + outcome_score(outcome.reverse()),
+                me: self.move_scores[me] + outcome_score(outcome),
             },
-            RPS::Scissors => {
-                match player2 {
-                    RPS::Rock => (0, 6),
-                    RPS::Paper => (6, 0),
-                    RPS::Scissors => (3, 3),
-                }
-            }
         }
     }
-}
 
-fn day1() {
-    let mut total_calories: Vec<usize> = vec![];
+    // plays every round of the guide, returning each round and the totals
+    pub fn play_guide(&self, guide: &[(usize, usize)], column: Column) -> (Vec<RPSRound>, Score) {
+        let rounds: Vec<RPSRound> = guide.iter()
+            .map(|(opponent, second)| {
+                let me = match column {
+                    Column::Move => *second,
+                    Column::Outcome => self.move_for(*opponent, Outcome::ALL[*second]),
+                };
+                self.play(*opponent, me)
+            })
+            .collect();
 
-    let lines_iter = io::BufReader::new(
-        File::open("./inputs/2022/day1/input").expect("can't open input file")
-        ).lines();
+        let total = rounds.iter()
+            .fold(Score { opponent: 0, me: 0 }, |total, round| Score {
+                opponent: total.opponent + round.score.opponent,
+                me: total.me + round.score.me,
+            });
 
+        (rounds, total)
+    }
 
-    let mut elf_total: usize = 0;
-    for line in lines_iter {
-        if let Ok(x) = line {
-            if x.trim().is_empty() {
-                total_calories.push(elf_total);
-                elf_total = 0;
-            }
-            else {
-                let single_item: usize = x.parse().expect("unable to parse a valid usize from input");
-                elf_total += single_item;
-            }
-        }
+    #[allow(dead_code)]
+    pub fn name(&self, index: usize) -> &'static str {
+        self.moves[index]
     }
+}
+
+fn day1() {
+    let reader = BufReader::new(File::open("./inputs/2022/day1/input").expect("can't open input file"));
+    let mut total_calories = parse_day1(reader).unwrap();
 
     total_calories.sort_unstable();
 
@@ -899,4 +1244,311 @@ fn day1() {
 
 }
 
+// sums the calories carried by each elf
+fn parse_day1(reader: impl BufRead) -> Result<Vec<usize>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::sections(&input)
+        .iter()
+        .map(|elf| elf.iter().map(|line| line.trim().parse::<usize>()).sum())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day8_visibility_and_scores() {
+        let input = "30373
+25512
+65332
+33549
+35390
+";
+        let trees = parse_day8(input.as_bytes()).unwrap();
+
+        assert_eq!(21, trees.count_visible());
+        assert_eq!(8, trees.highest_scene_score());
+        assert_eq!(vec![true, true, true, false, true], trees.visibility()[1]);
+        assert_eq!(vec![0, 1, 4, 1, 0], trees.scene_scores()[1]);
+        assert_eq!(8, trees.scene_scores()[3][2]);
+
+        let error = parse_day8("123\n12\n".as_bytes()).err().unwrap();
+        assert_eq!(ParseError::new(2, 3, "expected a row of 3 trees"), error);
+    }
+
+    #[test]
+    fn day6_markers() {
+        let cases = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+
+        for (input, packet, message) in cases {
+            assert_eq!(Some(packet), find_marker(input.as_bytes(), 4).unwrap());
+            assert_eq!(Some(message), find_marker(input.as_bytes(), 14).unwrap());
+        }
+
+        assert_eq!(None, find_marker("aaaa".as_bytes(), 2).unwrap());
+        assert_eq!(vec![2, 4, 5], find_all_markers("abbab".as_bytes(), 2).unwrap());
+    }
+
+    #[test]
+    fn day6_large_stream() {
+        // a repeating pattern of 200 distinct bytes spread over several chunks
+        let stream: Vec<u8> = (0..3_000_000).map(|i| (i % 200) as u8).collect();
+        let markers = find_all_markers(stream.as_slice(), 200).unwrap();
+
+        assert_eq!(3_000_000 - 199, markers.len());
+        assert_eq!(Some(&200), markers.first());
+        assert_eq!(None, find_marker(stream.as_slice(), 201).unwrap());
+    }
+
+    #[test]
+    fn day3_item_sets() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+        let rucksacks = parse_day3(input.as_bytes()).unwrap();
+
+        assert_eq!(vec!['p'], rucksacks[0].shared_items().items().collect::<Vec<_>>());
+        assert_eq!(157u32, rucksacks.iter().map(|x| x.shared_items().priority_sum()).sum());
+
+        let badges = group_badges(&rucksacks, 3);
+        assert_eq!(vec![18, 52], badges.iter().map(ItemSet::priority_sum).collect::<Vec<_>>());
+
+        // a pair of rucksacks shares more than just the badge
+        let pairs = group_badges(&rucksacks[..2], 2);
+        assert_eq!(ItemSet::from_items("rsFMf"), pairs[0]);
+    }
+
+    #[test]
+    fn day3_priorities() {
+        for priority in 1..=52 {
+            let item = ItemSet::item(priority).unwrap();
+            assert_eq!(Some(priority), ItemSet::priority(item));
+            assert!(ItemSet::all().contains(item));
+        }
+        assert_eq!(52, ItemSet::all().len());
+        assert_eq!(None, ItemSet::priority('1'));
+
+        let set = ItemSet::from_items("abZ") | ItemSet::from_items("c");
+        assert_eq!(vec![1, 2, 3, 52], set.priorities().collect::<Vec<_>>());
+        assert!((set & ItemSet::from_items("xyz")).is_empty());
+    }
 
+    #[test]
+    fn day2_strategy_guide() {
+        let game = RPSGame::classic();
+        let guide = parse_day2("A Y\nB X\nC Z\n".as_bytes(), &game, "ABC", "XYZ", Column::Move).unwrap();
+
+        let (rounds, total) = game.play_guide(&guide, Column::Move);
+        assert_eq!(Score { opponent: 15, me: 15 }, total);
+        assert_eq!(Outcome::Win, rounds[0].outcome);
+        assert_eq!(Score { opponent: 1, me: 8 }, rounds[0].score);
+        assert_eq!("Paper", game.name(rounds[0].me));
+
+        let (rounds, total) = game.play_guide(&guide, Column::Outcome);
+        assert_eq!(12, total.me);
+        assert_eq!(vec![0, 0, 0], rounds.iter().map(|x| x.me).collect::<Vec<_>>());
+
+        let error = parse_day2("A W\n".as_bytes(), &game, "ABC", "XYZ", Column::Move).err().unwrap();
+        assert_eq!(ParseError::new(1, 3, "expected one of `XYZ`"), error);
+
+        // symbols past the moves or outcomes the column can hold
+        let error = parse_day2("D X\n".as_bytes(), &game, "ABCD", "XYZ", Column::Move).err().unwrap();
+        assert_eq!(ParseError::new(1, 1, "`D` is past the 3 moves"), error);
+        let error = parse_day2("A W\n".as_bytes(), &game, "ABC", "XYZW", Column::Outcome).err().unwrap();
+        assert_eq!(ParseError::new(1, 3, "`W` is past the 3 outcomes"), error);
+    }
+
+    #[test]
+    fn day2_more_moves() {
+        let game = RPSGame::cyclic(vec!["Rock", "Paper", "Scissors", "Spock", "Lizard"], vec![1, 2, 3, 4, 5], [0, 3, 6]).unwrap();
+        let (rock, paper, scissors, spock, lizard) = (0, 1, 2, 3, 4);
+
+        assert_eq!(Outcome::Win, game.outcome(spock, scissors));
+        assert_eq!(Outcome::Win, game.outcome(lizard, spock));
+        assert_eq!(Outcome::Win, game.outcome(rock, lizard));
+        assert_eq!(Outcome::Loss, game.outcome(paper, lizard));
+        assert_eq!(Outcome::Draw, game.outcome(paper, paper));
+
+        // every move beats exactly half of the others
+        for me in 0..5 {
+            assert_eq!(2, (0..5).filter(|x| game.outcome(me, *x) == Outcome::Win).count());
+            for outcome in Outcome::ALL {
+                assert_eq!(outcome, game.outcome(game.move_for(me, outcome), me));
+            }
+        }
+
+        // an even cycle has pairs that beat each other
+        assert!(RPSGame::cyclic(vec!["A", "B", "C", "D"], vec![1, 2, 3, 4], [0, 3, 6]).is_err());
+    }
+
+    #[test]
+    fn day2_custom_rules() {
+        // the well swallows rock and scissors, but paper covers it
+        let (rock, paper, scissors, well) = (0, 1, 2, 3);
+        let rules = [(paper, rock), (scissors, paper), (rock, scissors), (well, rock), (well, scissors), (paper, well)];
+        let game = RPSGame::new(vec!["Rock", "Paper", "Scissors", "Well"], vec![1, 2, 3, 4], [0, 3, 6], &rules).unwrap();
+
+        assert_eq!(Outcome::Win, game.outcome(well, scissors));
+        assert_eq!(Outcome::Loss, game.outcome(well, paper));
+        assert_eq!(paper, game.move_for(well, Outcome::Win));
+        assert_eq!(rock, game.move_for(well, Outcome::Loss));
+
+        let guide = parse_day2("D Y\n".as_bytes(), &game, "ABCD", "XYZ", Column::Outcome).unwrap();
+        assert_eq!(Score { opponent: 4 + 3, me: 4 + 3 }, game.play_guide(&guide, Column::Outcome).1);
+
+        // nothing beats the well without paper
+        let error = RPSGame::new(vec!["Rock", "Paper", "Scissors", "Well"], vec![1, 2, 3, 4], [0, 3, 6], &rules[..5]).err();
+        assert_eq!(Some("Well can't be beaten".to_string()), error);
+        let error = RPSGame::new(vec!["Rock", "Paper"], vec![1, 2], [0, 3, 6], &[(0, 1), (1, 0)]).err();
+        assert_eq!(Some("Paper and Rock can't both beat each other".to_string()), error);
+    }
+
+    const DAY5_INPUT: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn day5_cranes() {
+        let ship = parse_day5(DAY5_INPUT.as_bytes()).unwrap();
+
+        let mut one_at_a_time = ship.clone();
+        one_at_a_time.execute(Crane::OneAtATime).unwrap();
+        assert_eq!("CMZ", one_at_a_time.read_top());
+
+        let mut all_at_once = ship.clone();
+        all_at_once.execute(Crane::AllAtOnce).unwrap();
+        assert_eq!("MCD", all_at_once.read_top());
+
+        // lifting two at a time moves [Z] [N] [D] as [N] [D] then [Z]
+        let mut batched = ship.clone();
+        batched.execute(Crane::Batch(2)).unwrap();
+        assert_eq!("MCZ", batched.read_top());
+    }
+
+    #[test]
+    fn day5_render_and_trace() {
+        let mut ship = parse_day5(DAY5_INPUT.as_bytes()).unwrap();
+        let drawing = DAY5_INPUT.split("\n\n").next().unwrap();
+        assert_eq!(drawing, ship.render());
+
+        let steps = ship.trace(Crane::OneAtATime).unwrap();
+        assert_eq!(4, steps.len());
+        assert_eq!("move 1 from 2 to 1
+[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ", steps[0]);
+    }
+
+    #[test]
+    fn day5_invalid_moves() {
+        let mut ship = parse_day5(DAY5_INPUT.as_bytes()).unwrap();
+        ship.movements = vec![Movement { num: 4, from: 1, to: 2 }];
+        assert_eq!(Err(MoveError::EmptyStack { step: 1, stack: 1 }), ship.execute(Crane::AllAtOnce));
+        assert_eq!("ZN", ship.hold[0].iter().collect::<String>());
+
+        ship.movements = vec![Movement { num: 1, from: 1, to: 2 }, Movement { num: 1, from: 4, to: 2 }];
+        assert_eq!(Err(MoveError::UnknownStack { step: 2, stack: 4 }), ship.execute(Crane::OneAtATime));
+
+        let error = parse_day5("[A] [B]\n 1 \n".as_bytes()).err().unwrap();
+        assert_eq!(ParseError::new(1, 6, "crate is outside of the labelled stacks"), error);
+    }
+
+    const DAY7_INPUT: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn day7_directory_sizes() {
+        let fs = parse_day7(DAY7_INPUT.as_bytes()).unwrap();
+
+        let part1: usize = fs.dirs()
+            .map(|dir| dir.size())
+            .filter(|size| *size <= 100_000)
+            .sum();
+        assert_eq!(95437, part1);
+        assert_eq!(48381165, fs.root().size());
+
+        let paths: Vec<String> = fs.dirs().map(|dir| dir.path()).collect();
+        assert_eq!(vec!["/", "/a", "/a/e", "/d"], paths);
+
+        assert_eq!("584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/", fs.du());
+    }
+
+    #[test]
+    fn day7_path_resolution() {
+        let mut fs = parse_day7(DAY7_INPUT.as_bytes()).unwrap();
+
+        let e = fs.resolve("/a/e").unwrap();
+        fs.cd("/a/e").unwrap();
+        assert_eq!(e, fs.resolve(".").unwrap());
+        assert_eq!(fs.resolve("/d").unwrap(), fs.resolve("../../d").unwrap());
+        assert_eq!(fs.resolve("/").unwrap(), fs.resolve("../../../..").unwrap());
+        assert!(fs.resolve("/a/f").is_err());
+
+        // cached sizes are refreshed when something is added
+        fs.add_file("z", 16);
+        assert_eq!(600, fs.entry(e).size());
+        assert_eq!(48381181, fs.root().size());
+    }
+
+    #[test]
+    fn day7_tree() {
+        let fs = parse_day7(DAY7_INPUT.as_bytes()).unwrap();
+
+        let expected = "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)";
+        assert_eq!(expected, fs.tree());
+    }
+}
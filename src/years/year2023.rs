@@ -1,14 +1,25 @@
 use std::{
-    cmp::{self, Ordering},
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     fs::File,
     io::{BufRead, BufReader},
-    iter::{self, once},
+    ops::Range,
 };
 
 use itertools::Itertools;
 
-use crate::{AdventYear, Year};
+use crate::{
+    utils::{
+        geometry::{Location, Polygon},
+        grid::Grid,
+        interval::{Interval, RangeSet},
+        parse::{self, Line, ParseError},
+        polynomial::{PolynomialSequence, SequenceError},
+        quadratic::{self, QuadraticError},
+        trie::Trie,
+    },
+    AdventYear, Year,
+};
 
 pub fn init() -> Box<dyn AdventYear> {
     let days: Vec<Box<dyn Fn()>> = vec![
@@ -30,449 +41,443 @@ pub fn init() -> Box<dyn AdventYear> {
 
 fn day11() {
     let reader = BufReader::new(File::open("./input/2023/day11").unwrap());
-    let mut map = day11_parse(reader);
-    map.cosmic_inflate();
-    println!("Part 1 {}", map.sum_pair_paths(2));
-    println!("Part 2 {}", map.sum_pair_paths(1000000));
+    let map = day11_parse(reader).unwrap();
+    let distances = map.pair_distances();
+    println!("Part 1 {}", distances.at(2));
+    println!("Part 2 {}", distances.at(1000000));
 }
 
-fn day11_parse(reader: impl BufRead) -> GalaxyMap {
+fn day11_parse(reader: impl BufRead) -> Result<GalaxyMap, ParseError> {
+    let input = parse::read_input(reader)?;
     let mut galaxies = Vec::new();
-    let map = reader
-        .lines()
-        .map(|x| x.unwrap())
-        .enumerate()
-        .map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col, c)| match c {
-                    '.' => ST::Empty,
-                    '#' => {
-                        galaxies.push((row, col));
-                        ST::Galaxy
-                    }
-                    x => panic!("invalid character: {}", x),
-                })
-                .collect_vec()
-        })
-        .collect_vec();
+    let mut width = None;
+    let mut height = 0;
 
-    GalaxyMap { map, galaxies }
-}
+    for (row, line) in parse::lines(&input).enumerate() {
+        let width = *width.get_or_insert(line.text.len());
+        if line.text.len() != width {
+            return Err(line.error_at(0, format!("expected a row of {} tiles", width)));
+        }
 
-#[derive(Debug, Clone)]
-enum ST {
-    Empty,
-    Galaxy,
-    Inflated,
+        for (col, c) in line.text.chars().enumerate() {
+            match c {
+                '.' => {}
+                '#' => galaxies.push((row, col)),
+                x => return Err(line.error_at(col, format!("invalid character `{}`", x))),
+            }
+        }
+        height += 1;
+    }
+
+    Ok(GalaxyMap::new(height, width.unwrap_or(0), galaxies))
 }
 
-#[derive(Debug)]
+/// Galaxy positions within a universe, without storing any empty space
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct GalaxyMap {
-    map: Vec<Vec<ST>>,
+    height: usize,
+    width: usize,
     galaxies: Vec<(usize, usize)>,
+    // rows and columns without a galaxy
+    empty_rows: RangeSet<usize>,
+    empty_cols: RangeSet<usize>,
 }
 
 impl GalaxyMap {
-    // calculate cosmic inflation
-    pub fn cosmic_inflate(&mut self) {
-        let mut empty_cols = vec![true; self.map[0].len()];
-        let mut empty_rows: Vec<usize> = Vec::new();
-
-        // find empty rows and columns
-        for i in (0..(self.map.len())).rev() {
-            let mut row_empty = true;
-
-            for (j, tile) in self.map[i].iter().enumerate() {
-                if let ST::Galaxy = tile {
-                    empty_cols[j] = false;
-                    row_empty = false;
-                }
-            }
+    pub fn new(height: usize, width: usize, galaxies: Vec<(usize, usize)>) -> GalaxyMap {
+        let mut empty_rows = RangeSet::from(Interval::new(0, height));
+        let mut empty_cols = RangeSet::from(Interval::new(0, width));
+        for (row, col) in galaxies.iter() {
+            empty_rows.remove(Interval::with_len(*row, 1));
+            empty_cols.remove(Interval::with_len(*col, 1));
+        }
 
-            if row_empty {
-                empty_rows.push(i);
-            }
+        GalaxyMap {
+            height,
+            width,
+            galaxies,
+            empty_rows,
+            empty_cols,
         }
+    }
+
+    /// Galaxy positions once every empty row and column is inflation_factor wide
+    ///
+    /// a factor of 0 removes the empty lines altogether
+    #[allow(dead_code)]
+    pub fn expanded_galaxies(&self, inflation_factor: usize) -> Vec<(usize, usize)> {
+        let rows = EmptyLines::new(&self.empty_rows);
+        let cols = EmptyLines::new(&self.empty_cols);
+
+        self.galaxies
+            .iter()
+            .map(|(row, col)| {
+                (
+                    rows.expand(*row, inflation_factor),
+                    cols.expand(*col, inflation_factor),
+                )
+            })
+            .collect()
+    }
 
-        for row in empty_rows {
-            self.map[row]
-                .iter_mut()
-                .for_each(|tile| *tile = ST::Inflated);
+    /// The universe after inflation, empty space stays implicit so any factor fits
+    ///
+    /// a factor of 0 removes the empty lines altogether
+    #[allow(dead_code)]
+    pub fn expand(&self, inflation_factor: usize) -> GalaxyMap {
+        // each run of empty lines moves by the empty lines before it and grows
+        let expand_lines = |lines: &RangeSet<usize>| {
+            let empty = EmptyLines::new(lines);
+            lines
+                .iter()
+                .map(|x| {
+                    let start = empty.expand(x.start(), inflation_factor);
+                    Interval::with_len(start, x.len() * inflation_factor)
+                })
+                .collect::<RangeSet<_>>()
+        };
+        let expand_len = |len: usize, empty: usize| len - empty + empty * inflation_factor;
+
+        GalaxyMap {
+            height: expand_len(self.height, self.empty_rows.total_len()),
+            width: expand_len(self.width, self.empty_cols.total_len()),
+            galaxies: self.expanded_galaxies(inflation_factor),
+            empty_rows: expand_lines(&self.empty_rows),
+            empty_cols: expand_lines(&self.empty_cols),
         }
+    }
 
-        // replace Empty with Inflated in cols
-        for (col, empty) in empty_cols.into_iter().enumerate().rev() {
-            // skip non empty cols
-            if !empty {
-                continue;
-            }
+    // sums the distances between every pair of galaxies for any inflation factor
+    pub fn pair_distances(&self) -> DistanceSum {
+        let rows = self.galaxies.iter().map(|x| x.0).collect_vec();
+        let cols = self.galaxies.iter().map(|x| x.1).collect_vec();
 
-            for row in self.map.iter_mut() {
-                row[col] = ST::Inflated;
-            }
+        // empty lines before a galaxy only grow with its index, so the number
+        // crossed between two galaxies is the difference of their counts
+        let empty_rows = EmptyLines::new(&self.empty_rows);
+        let empty_cols = EmptyLines::new(&self.empty_cols);
+        let rows_before = rows.iter().map(|x| empty_rows.before(*x)).collect();
+        let cols_before = cols.iter().map(|x| empty_cols.before(*x)).collect();
+
+        DistanceSum {
+            unexpanded: sum_abs_differences(rows) + sum_abs_differences(cols),
+            empty_crossings: sum_abs_differences(rows_before) + sum_abs_differences(cols_before),
         }
     }
+}
 
-    pub fn sum_pair_paths(&mut self, inflation_factor: usize) -> usize {
-        let galaxies = &self.galaxies;
+/// Runs of empty lines, with how many empty lines come before each run
+struct EmptyLines {
+    runs: Vec<(Interval<usize>, usize)>,
+}
 
-        let mut sum: usize = 0;
+impl EmptyLines {
+    pub fn new(lines: &RangeSet<usize>) -> EmptyLines {
+        let mut total = 0;
+        let runs = lines
+            .iter()
+            .map(|x| {
+                let run = (*x, total);
+                total += x.len();
+                run
+            })
+            .collect();
 
-        for a in 0..galaxies.len() {
-            for b in (a + 1)..galaxies.len() {
-                let row_diff = galaxies[a].0.abs_diff(galaxies[b].0);
-                let col_diff = galaxies[a].1.abs_diff(galaxies[b].1);
-                let row_min = cmp::min(galaxies[a].0, galaxies[b].0);
-                let col_min = cmp::min(galaxies[a].1, galaxies[b].1);
+        EmptyLines { runs }
+    }
 
-                // calculate row distance
-                for r in row_min..(row_min + row_diff) {
-                    if let ST::Inflated = self.map[r][col_min] {
-                        sum += inflation_factor;
-                    } else {
-                        sum += 1;
-                    }
-                }
+    // number of empty lines below index
+    pub fn before(&self, index: usize) -> usize {
+        let runs = self.runs.partition_point(|(x, _)| x.start() < index);
 
-                // calculate col distance
-                for c in col_min..(col_diff + col_min) {
-                    if let ST::Inflated = self.map[row_min][c] {
-                        sum += inflation_factor;
-                    } else {
-                        sum += 1;
-                    }
-                }
-            }
+        match runs.checked_sub(1).map(|i| self.runs[i]) {
+            Some((run, earlier)) => earlier + run.end().min(index) - run.start(),
+            None => 0,
         }
+    }
 
-        sum
+    // where index ends up once every empty line is inflation_factor wide
+    pub fn expand(&self, index: usize, inflation_factor: usize) -> usize {
+        let before = self.before(index);
+        index - before + before * inflation_factor
     }
 }
 
-impl ToString for GalaxyMap {
-    fn to_string(&self) -> String {
-        self.map
-            .iter()
-            .flat_map(|row| {
-                row.iter()
-                    .map(|st| match st {
-                        ST::Empty => '.',
-                        ST::Galaxy => '#',
-                        ST::Inflated => '~',
-                    })
-                    .chain(once('\n'))
-            })
-            .collect()
+// sum of |a - b| over every pair of values, in O(n log n)
+fn sum_abs_differences(mut values: Vec<usize>) -> usize {
+    values.sort_unstable();
+
+    let mut prefix = 0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            // every earlier value is smaller, so each pair contributes value - earlier
+            let sum = value * i - prefix;
+            prefix += value;
+            sum
+        })
+        .sum()
+}
+
+/// Total distance between galaxy pairs as a linear function of the inflation factor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DistanceSum {
+    // total distance without any inflation
+    unexpanded: usize,
+    // how many empty rows and columns are crossed in total
+    empty_crossings: usize,
+}
+
+impl DistanceSum {
+    /// Each empty line crossed counts inflation_factor instead of 1
+    ///
+    /// every crossing is already counted once in the unexpanded distance, so
+    /// taking them away first can't underflow, even for a factor of 0
+    pub fn at(&self, inflation_factor: usize) -> usize {
+        self.unexpanded - self.empty_crossings + self.empty_crossings * inflation_factor
+    }
+}
+
+impl fmt::Display for GalaxyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = vec![vec!['.'; self.width]; self.height];
+        for (row, col) in self.galaxies.iter() {
+            map[*row][*col] = '#';
+        }
+
+        for row in map {
+            writeln!(f, "{}", row.into_iter().collect::<String>())?;
+        }
+        Ok(())
     }
 }
 
 fn day10() {
     let reader = BufReader::new(File::open("./input/2023/day10").unwrap());
-    let mut land = day10_parse(reader);
+    let land = day10_parse(reader).unwrap();
 
     println!("Part 1: {}", land.find_furthest_length());
     println!("Part 2: {}", land.count_enclosed_ground());
 }
 
-fn day10_parse(reader: impl BufRead) -> Landscape {
+fn day10_parse(reader: impl BufRead) -> Result<Landscape, ParseError> {
+    let input = parse::read_input(reader)?;
     let mut start = None;
-    let grid = reader
-        .lines()
-        .map(|x| x.unwrap())
+    let grid = parse::lines(&input)
         .enumerate()
         .map(|(i, line)| {
-            line.chars()
+            line.text
+                .chars()
                 .enumerate()
                 .map(|(j, c)| {
                     if c == 'S' {
-                        assert!(start.is_none(), "already found start, cannot have 2");
+                        if start.is_some() {
+                            return Err(line.error_at(j, "already found start, cannot have 2"));
+                        }
                         start = Some((i, j));
                     }
-                    c.into()
+                    Tile::try_from(c).map_err(|e| line.error_at(j, e))
                 })
-                .collect_vec()
+                .collect::<Result<Vec<_>, _>>()
         })
-        .collect_vec();
+        .collect::<Result<Vec<_>, _>>()?;
 
-    Landscape {
-        grid,
-        start: start.unwrap(),
-    }
+    let start = start.ok_or_else(|| ParseError::new(1, 1, "no start tile found"))?;
+    let mut land = Landscape { grid, start };
+    land.infer_start()
+        .map_err(|e| ParseError::new(start.0 + 1, start.1 + 1, e))?;
+
+    Ok(land)
 }
 
+// north, east, south, west
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
 struct Landscape {
     grid: Vec<Vec<Tile>>,
     start: (usize, usize),
 }
 
 impl Landscape {
-    fn connections(&self, index: (usize, usize), ignore_visited: bool) -> Vec<(usize, usize)> {
-        let mut connections = Vec::new();
-        let (row, col) = index;
-        let current = &self.grid[row][col];
-
-        // check north
-        if row > 0 {
-            if (!self.grid[row - 1][col].visited || ignore_visited)
-                && current.connections[0]
-                && self.grid[row - 1][col].connections[2]
-            {
-                connections.push((row - 1, col));
-            }
-        }
-        // check south
-        if row < (self.grid.len() - 1) {
-            if (!self.grid[row + 1][col].visited || ignore_visited)
-                && current.connections[2]
-                && self.grid[row + 1][col].connections[0]
-            {
-                connections.push((row + 1, col));
-            }
-        }
-        // check west
-        if col > 0 {
-            if (!self.grid[row][col - 1].visited || ignore_visited)
-                && current.connections[3]
-                && self.grid[row][col - 1].connections[1]
-            {
-                connections.push((row, col - 1));
-            }
-        }
-        // check east
-        if col < self.grid[row].len() - 1 {
-            if (!self.grid[row][col + 1].visited || ignore_visited)
-                && current.connections[1]
-                && self.grid[row][col + 1].connections[3]
-            {
-                connections.push((row, col + 1));
-            }
-        }
-        connections
-    }
-
-    pub fn find_furthest_length(&mut self) -> usize {
-        // breadth first search
-
-        // initialize distance and processing queue
-        let mut p_queue = VecDeque::new();
-        p_queue.push_front(self.start.clone());
-        self.grid[self.start.0][self.start.1].length = Some(0);
-
-        // perform search
-        while let Some((row, col)) = p_queue.pop_back() {
-            // mark current node as visited
-            self.grid[row][col].visited = true;
-
-            let connections = self.connections((row, col), false);
-            for (r, c) in connections {
-                if let Some(len) = self.grid[r][c].length {
-                    // found loop connection point (also the distance furthest from start)
-
-                    // set loop tiles
-                    self.set_loop((r, c), (row, col));
-
-                    // return lenght
-                    return len;
-                }
+    // the neighbouring tile in a direction, if it is on the grid
+    fn neighbour(&self, index: (usize, usize), direction: usize) -> Option<(usize, usize)> {
+        let (dr, dc) = DIRECTIONS[direction];
+        let row = index.0.checked_add_signed(dr)?;
+        let col = index.1.checked_add_signed(dc)?;
+
+        self.grid.get(row)?.get(col).map(|_| (row, col))
+    }
+
+    // replaces the start tile with the pipe which completes the loop
+    fn infer_start(&mut self) -> Result<(), &'static str> {
+        // directions where the neighbouring pipe points back at the start
+        let candidates = (0..4)
+            .filter(|direction| {
+                self.neighbour(self.start, *direction)
+                    .is_some_and(|(r, c)| self.grid[r][c].connections[(direction + 2) % 4])
+            })
+            .collect_vec();
 
-                // set parent
-                self.grid[r][c].parent = Some((row, col));
+        // more than two candidates means some of them are dead ends
+        for (a, b) in candidates.into_iter().tuple_combinations() {
+            let mut connections = [false; 4];
+            connections[a] = true;
+            connections[b] = true;
+            self.grid[self.start.0][self.start.1] = Tile::from_connections(connections);
 
-                // set lengths for connections and add them to processing queue
-                self.grid[r][c].length = Some(self.grid[row][col].length.unwrap() + 1);
-                p_queue.push_front((r, c));
+            if self.walk_loop().is_some() {
+                return Ok(());
             }
         }
 
-        panic!("unable to find loop (possibly invalid graph)");
+        self.grid[self.start.0][self.start.1] = Tile::from_connections([false; 4]);
+        Err("start tile isn't part of a loop")
     }
 
-    // counts the number of ground tiles enclosed by the loop
-    // find_furthest_length must be called before this function
-    pub fn count_enclosed_ground(&mut self) -> usize {
-        let mut contained = 0;
-
-        // iterate over every cell, updating even/odd counts
-        for row in 0..self.grid.len() {
-            let mut up: Option<bool> = None;
-
-            // first character
-            if self.grid[row][0].is_loop {
-                match self.grid[row][0].c {
-                    'L' => up = Some(true),
-                    'F' => up = Some(false),
-                    '|' => self.grid[row][0].left_even = !self.grid[row][0].left_even,
-                    _ => unreachable!(),
-                }
-            }
+    // follows the pipes from the start, failing if they don't lead back to it
+    fn walk_loop(&self) -> Option<Vec<(usize, usize)>> {
+        let mut path = vec![self.start];
+        let mut direction = self.grid[self.start.0][self.start.1]
+            .connections
+            .iter()
+            .position(|x| *x)?;
+        let mut current = self.start;
 
-            for col in 1..self.grid[row].len() {
-                // this tile is part of the loop, update raycast parity
-                if self.grid[row][col].is_loop && self.grid[row][col].c != '-' {
-                    if self.grid[row][col].c == '|' {
-                        self.grid[row][col].left_even = !self.grid[row][col - 1].left_even;
-                    // tile is corner piece
-                    } else {
-                        if let Some(up) = up.take() {
-                            match self.grid[row][col].c {
-                                'J' => {
-                                    // crossed, flip parity
-                                    if !up {
-                                        self.grid[row][col].left_even =
-                                            !self.grid[row][col - 1].left_even;
-                                    // no cross, don't flip parity
-                                    } else {
-                                        self.grid[row][col].left_even =
-                                            self.grid[row][col - 1].left_even;
-                                    }
-                                }
-                                '7' => {
-                                    // crossed, flip parity
-                                    if up {
-                                        self.grid[row][col].left_even =
-                                            !self.grid[row][col - 1].left_even;
-                                    // no cross, don't flip parity
-                                    } else {
-                                        self.grid[row][col].left_even =
-                                            self.grid[row][col - 1].left_even;
-                                    }
-                                }
-                                _ => {
-                                    unreachable!()
-                                }
-                            }
-                        } else {
-                            // save pipe direction
-                            match self.grid[row][col].c {
-                                'L' => up = Some(true),
-                                'F' => up = Some(false),
-                                _ => unreachable!(),
-                            }
-
-                            // maintain parity
-                            self.grid[row][col].left_even = self.grid[row][col - 1].left_even;
-                        }
-                    }
-                // this title is not part of the loop, maintain parity
-                } else {
-                    self.grid[row][col].left_even = self.grid[row][col - 1].left_even;
-                }
+        loop {
+            current = self.neighbour(current, direction)?;
 
-                // left is odd, increment count
-                if !self.grid[row][col].is_loop && !self.grid[row][col].left_even {
-                    contained += 1;
-                }
+            // leave by the end we didn't come in through
+            let tile = &self.grid[current.0][current.1];
+            let entry = (direction + 2) % 4;
+            if !tile.connections[entry] {
+                return None;
             }
-        }
-
-        contained
-    }
-
-    // sets all loop tiles
-    fn set_loop(&mut self, index_1: (usize, usize), index_2: (usize, usize)) {
-        let mut p_stack: Vec<(usize, usize)> = vec![index_1, index_2];
-
-        while let Some((row, col)) = p_stack.pop() {
-            if let Some((r, c)) = self.grid[row][col].parent {
-                p_stack.push((r, c));
+            if current == self.start {
+                return Some(path);
             }
+            direction = (0..4).find(|x| *x != entry && tile.connections[*x])?;
 
-            self.grid[row][col].is_loop = true;
+            path.push(current);
         }
-        assert!(self.grid[self.start.0][self.start.1].is_loop);
+    }
 
-        // determine what shape starting tile is
-        let connections = self.connections(self.start, true);
-        assert!(connections.len() >= 2);
+    // every tile of the loop through the start, in order starting with the start
+    pub fn main_loop(&self) -> Vec<(usize, usize)> {
+        self.walk_loop()
+            .expect("the start is always on a loop after parsing")
+    }
+
+    pub fn find_furthest_length(&self) -> usize {
+        self.main_loop().len() / 2
+    }
 
-        let connections = connections
+    // counts the number of ground tiles enclosed by the loop
+    pub fn count_enclosed_ground(&self) -> usize {
+        let vertices = self
+            .main_loop()
             .into_iter()
-            .filter(|(row, col)| self.grid[*row][*col].is_loop)
-            .collect_vec();
+            .map(|(row, col)| (row as i64, col as i64))
+            .collect();
 
-        assert_eq!(2, connections.len());
-
-        let mut dir: Vec<u8> = Vec::new();
-        for conn in connections {
-            // connection is north
-            if conn.0 < self.start.0 {
-                dir.push(0);
-            // connection is south
-            } else if conn.0 > self.start.0 {
-                dir.push(2);
-            // connection is east
-            } else if conn.1 > self.start.1 {
-                dir.push(1);
-            // connection is west
-            } else if conn.1 < self.start.1 {
-                dir.push(3);
-            } else {
-                panic!("invalid connection");
-            }
-        }
+        Polygon::new(vertices).interior_points() as usize
+    }
 
-        // sort by verticality (descending)
-        dir.sort_unstable_by(|a, b| {
-            if *a == 0 || *a == 2 {
-                if *b == 0 || *b == 2 {
-                    Ordering::Equal
-                } else {
-                    Ordering::Greater
-                }
-            } else {
-                if *b == 0 || *b == 2 {
-                    Ordering::Less
-                } else {
-                    Ordering::Equal
-                }
-            }
-        });
-        dir.reverse();
-
-        match dir[0] {
-            // one end above
-            0 => {
-                match dir[1] {
-                    // |
-                    2 => self.grid[self.start.0][self.start.1].c = '|',
-                    // L
-                    1 => self.grid[self.start.0][self.start.1].c = 'L',
-                    // J
-                    3 => self.grid[self.start.0][self.start.1].c = 'J',
-                    _ => unreachable!(),
-                }
-            }
-            // one end below
-            2 => match dir[1] {
-                0 => self.grid[self.start.0][self.start.1].c = '|',
-                1 => self.grid[self.start.0][self.start.1].c = 'F',
-                3 => self.grid[self.start.0][self.start.1].c = '7',
-                _ => unreachable!(),
-            },
-            // horizontal
-            x => {
-                assert!(x == 1 || x == 3);
-                assert!(dir[1] == 1 || dir[1] == 3);
-                self.grid[self.start.0][self.start.1].c = '-';
-            }
+    // classifies every tile, with the loop itself as the boundary
+    pub fn locations(&self) -> Vec<Vec<Location>> {
+        let mut on_loop = vec![vec![false; self.grid[0].len()]; self.grid.len()];
+        for (row, col) in self.main_loop() {
+            on_loop[row][col] = true;
         }
+
+        self.grid
+            .iter()
+            .zip(on_loop)
+            .map(|(tiles, on_loop)| {
+                // a ray along the row crosses the loop at every pipe heading north
+                let mut inside = false;
+                tiles
+                    .iter()
+                    .zip(on_loop)
+                    .map(|(tile, on_loop)| {
+                        if on_loop {
+                            inside ^= tile.connections[0];
+                            Location::Boundary
+                        } else if inside {
+                            Location::Inside
+                        } else {
+                            Location::Outside
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // draws the maze with box drawing characters, colouring the loop, inside and outside
+    #[allow(dead_code)]
+    pub fn render(&self) -> String {
+        self.grid
+            .iter()
+            .zip(self.locations())
+            .map(|(tiles, locations)| {
+                let mut line = tiles
+                    .iter()
+                    .zip(locations)
+                    .map(|(tile, location)| {
+                        let colour = match location {
+                            Location::Boundary => "\x1b[1;33m",
+                            Location::Inside => "\x1b[32m",
+                            Location::Outside => "\x1b[2;34m",
+                        };
+                        format!("{}{}", colour, tile.box_drawing())
+                    })
+                    .collect::<String>();
+                line.push_str("\x1b[0m");
+                line
+            })
+            .join("\n")
     }
 }
 
 struct Tile {
     pub c: char,
-    pub is_loop: bool,
     // north, east, south, west
     pub connections: [bool; 4],
-    pub visited: bool,
-    pub parent: Option<(usize, usize)>,
-    pub left_even: bool,
-    pub length: Option<usize>,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl Tile {
+    fn from_connections(connections: [bool; 4]) -> Tile {
+        let c = match connections {
+            [true, false, true, false] => '|',
+            [false, true, false, true] => '-',
+            [true, true, false, false] => 'L',
+            [true, false, false, true] => 'J',
+            [false, false, true, true] => '7',
+            [false, true, true, false] => 'F',
+            _ => '.',
+        };
+
+        Tile { c, connections }
+    }
+
+    fn box_drawing(&self) -> char {
+        match self.c {
+            '|' => '│',
+            '-' => '─',
+            'L' => '└',
+            'J' => '┘',
+            '7' => '┐',
+            'F' => '┌',
+            _ => '·',
+        }
+    }
+}
+
+impl TryFrom<char> for Tile {
+    type Error = &'static str;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         let connections = match value {
             '|' => [true, false, true, false],
             '-' => [false, true, false, true],
@@ -481,115 +486,66 @@ impl From<char> for Tile {
             '7' => [false, false, true, true],
             'F' => [false, true, true, false],
             '.' => [false, false, false, false],
-            'S' => [true, true, true, true],
-            x => panic!("invalid character: {}", x),
+            // the real shape is inferred once the whole grid is known
+            'S' => [false, false, false, false],
+            _ => return Err("invalid pipe character"),
         };
 
-        Tile {
+        Ok(Tile {
             c: value,
-            is_loop: false,
             connections,
-            visited: false,
-            parent: None,
-            left_even: true,
-            length: None,
-        }
+        })
     }
 }
 
 fn day9() {
     let reader = BufReader::new(File::open("./input/2023/day9").unwrap());
-    let history = day9_parse(reader);
-    let (p2_result, p1_result) = history.iter().fold((0i64, 0i64), |(back, forward), diffs| {
-        let (b, f) = day9_predict_values(diffs);
-        (back + b, forward + f)
-    });
+    let history = day9_parse(reader).unwrap();
+    let (p2_result, p1_result) = history
+        .iter()
+        .fold((0i128, 0i128), |(back, forward), values| {
+            let (b, f) = day9_predict_values(values).unwrap();
+            (back + b, forward + f)
+        });
     println!("Part 1: {}", p1_result);
     println!("Part 2: {}", p2_result);
 }
 
-fn day9_predict_values(values: &Vec<i64>) -> (i64, i64) {
-    let mut differences: Vec<Vec<i64>> = Vec::new();
-    let mut all_zero = false;
-
-    // find differences while differences are not all 0
-    while !all_zero {
-        all_zero = true;
-        let result;
-        // scope for values reference
-        {
-            let mut values = values;
-            if differences.len() > 0 {
-                values = differences.last().unwrap();
-            }
-
-            result = values
-                .iter()
-                .tuple_windows()
-                .map(|(a, b)| {
-                    let diff = b - a;
-                    if all_zero && diff != 0 {
-                        all_zero = false;
-                    }
-                    diff
-                })
-                .collect_vec();
-        } // drop values reference, so differences can be borrowed as mutable
+// predicts the values one step before and after the history
+fn day9_predict_values(values: &[i64]) -> Result<(i128, i128), SequenceError> {
+    let sequence = PolynomialSequence::new(values)?;
 
-        differences.push(result);
-    }
-
-    let mut diff_iter = once(values).chain(differences.iter()).rev();
-    let init = diff_iter.next().unwrap();
-    let mut forward_prediction: i64 = *init.last().unwrap();
-    let mut backward_prediction: i64 = *init.first().unwrap();
-    assert!(forward_prediction == 0);
-    for diffs in diff_iter {
-        forward_prediction = diffs.last().unwrap() + forward_prediction;
-        backward_prediction = diffs.first().unwrap() - backward_prediction;
-    }
-
-    (backward_prediction, forward_prediction)
+    Ok((sequence.backward(1)?, sequence.forward(1)?))
 }
 
-fn day9_parse(reader: impl BufRead) -> Vec<Vec<i64>> {
-    reader
-        .lines()
-        .map(|x| x.unwrap())
-        .map(|line| {
-            line.split_whitespace()
-                .map(|x| x.parse().unwrap())
-                .collect_vec()
-        })
-        .collect_vec()
+fn day9_parse(reader: impl BufRead) -> Result<Vec<Vec<i64>>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::lines(&input).map(|line| line.tokens()).collect()
 }
 
 fn day8() {
     let reader = BufReader::new(File::open("./input/2023/day8").unwrap());
-    let (instructions, mut adjacency) = day8_parse(reader);
+    let (instructions, mut adjacency) = day8_parse(reader).unwrap();
 
     println!(
         "Part 1 Num Steps: {}",
-        day8_p1(&instructions, &mut adjacency)
+        day8_p1(&instructions, &mut adjacency).unwrap()
     );
 
     println!(
         "Part 2 Num Steps: {}",
-        day8_p2(&instructions, &mut adjacency)
+        day8_p2(&instructions, &mut adjacency).unwrap()
     );
 }
 
-fn day8_p2(instructions: &str, adjacency: &mut AdjacencyGraph) -> u64 {
+// none when a path leads to a node that isn't in the graph
+fn day8_p2(instructions: &str, adjacency: &mut AdjacencyGraph) -> Option<u64> {
     let nodes = adjacency
         .adjacency
-        .iter()
-        .filter_map(|(key, _)| -> Option<&str> {
-            if key.as_bytes()[2] as char == 'A' {
-                Some(key)
-            } else {
-                None
-            }
-        })
+        .keys()
+        .filter(|key| key.ends_with('A'))
+        .map(|key| key.as_str())
         .collect_vec();
 
     let mut step_counts = nodes
@@ -599,18 +555,18 @@ fn day8_p2(instructions: &str, adjacency: &mut AdjacencyGraph) -> u64 {
             let mut index = 0;
             let mut steps = 0;
 
-            while node.as_bytes()[2] as char != 'Z' {
+            while !node.ends_with('Z') {
                 let left = instructions.as_bytes()[index] as char == 'L';
-                node = adjacency.turn(node, left);
+                node = adjacency.turn(node, left)?;
                 steps += 1;
 
                 // increment instruction index
                 index = (index + 1) % instructions.len();
             }
 
-            steps
+            Some(steps)
         })
-        .collect_vec();
+        .collect::<Option<Vec<_>>>()?;
 
     // sort descending
     step_counts.sort_unstable_by(|a, b| a.cmp(b).reverse());
@@ -624,54 +580,65 @@ fn day8_p2(instructions: &str, adjacency: &mut AdjacencyGraph) -> u64 {
         lcm = lcm * steps / gcf;
     }
 
-    lcm as u64
+    Some(lcm as u64)
 }
 
-fn day8_p1(instructions: &str, adjacency: &mut AdjacencyGraph) -> u64 {
+// none when the path leads to a node that isn't in the graph
+fn day8_p1(instructions: &str, adjacency: &mut AdjacencyGraph) -> Option<u64> {
     let mut node = "AAA";
     let mut index = 0;
     let mut steps = 0;
 
     while node != "ZZZ" {
         let left = instructions.as_bytes()[index] as char == 'L';
-        node = adjacency.turn(node, left);
+        node = adjacency.turn(node, left)?;
         steps += 1;
 
         // increment instruction index
         index = (index + 1) % instructions.len();
     }
 
-    steps
+    Some(steps)
 }
 
-fn day8_parse(reader: impl BufRead) -> (String, AdjacencyGraph) {
-    let mut lines = reader.lines().map(|x| x.unwrap());
+fn day8_parse(reader: impl BufRead) -> Result<(String, AdjacencyGraph), ParseError> {
+    let input = parse::read_input(reader)?;
+    let mut lines = parse::lines(&input);
+
     // read directions
-    let directions = lines.next().unwrap();
-    lines.next();
+    let directions = lines
+        .next()
+        .ok_or_else(|| ParseError::new(1, 1, "missing directions"))?;
+    if directions.text.is_empty() {
+        return Err(directions.error("missing directions"));
+    }
+    if let Some(i) = directions.text.find(|c| c != 'L' && c != 'R') {
+        return Err(directions.error_at(i, "directions can only be `L` or `R`"));
+    }
 
-    // read nodes
-    let adjacency = lines.fold(HashMap::new(), |mut adjacency, line| {
-        let mut nodes: Vec<String> = line
-            .split([' ', ',', '(', ')'])
-            .filter_map(|x| {
-                if x.len() == 3 {
-                    Some(x.to_owned())
-                } else {
-                    None
-                }
-            })
-            .collect_vec();
+    // node names are three letters or digits
+    let name = |x: Line| {
+        if x.text.len() == 3 && x.text.chars().all(|c| c.is_ascii_alphanumeric()) {
+            Ok(x.text.to_owned())
+        } else {
+            Err(x.error("expected a three character node name"))
+        }
+    };
 
-        let right = nodes.pop().unwrap();
-        let left = nodes.pop().unwrap();
-        let node = nodes.pop().unwrap();
+    // read nodes
+    let mut adjacency = HashMap::new();
+    for line in lines.filter(|line| !line.text.is_empty()) {
+        let (node, choices) = line.split_once('=')?;
+        let (left, right) = choices
+            .strip_prefix("(")
+            .and_then(|x| x.strip_suffix(")"))
+            .ok_or_else(|| choices.error("expected `(left, right)`"))?
+            .split_once(',')?;
 
-        adjacency.insert(node, (left, right));
-        adjacency
-    });
+        adjacency.insert(name(node)?, (name(left)?, name(right)?));
+    }
 
-    (directions, AdjacencyGraph { adjacency })
+    Ok((directions.text.to_owned(), AdjacencyGraph { adjacency }))
 }
 
 struct AdjacencyGraph {
@@ -679,163 +646,152 @@ struct AdjacencyGraph {
 }
 
 impl AdjacencyGraph {
-    pub fn turn<'a, 'b>(&'a self, node: &'b str, left: bool) -> &'a str {
-        let choice = self.adjacency.get(node).unwrap();
+    // the next node, none when node isn't in the graph
+    pub fn turn<'a>(&'a self, node: &str, left: bool) -> Option<&'a str> {
+        let choice = self.adjacency.get(node)?;
 
         if left {
-            &choice.0
+            Some(&choice.0)
         } else {
-            &choice.1
+            Some(&choice.1)
         }
     }
 }
 
 fn day7() {
     let reader = BufReader::new(File::open("./input/2023/day7").unwrap());
-    let mut bids = day7_parse(reader);
-    // sort by hands with weakest hand first
-    bids.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-    println!("Part 2: Winnings = {}", day7p2_logic(&bids));
+    let input = parse::read_input(reader).unwrap();
+
+    let mut bids = day7_parse(input.as_bytes(), &CamelRules::standard()).unwrap();
+    println!("Part 1: Winnings = {}", rank_and_score(&mut bids));
+
+    let mut bids = day7_parse(input.as_bytes(), &CamelRules::jokers()).unwrap();
+    println!("Part 2: Winnings = {}", rank_and_score(&mut bids));
 }
 
-fn day7p2_logic(bids: &Vec<(Hand, u64)>) -> u64 {
+// sorts bids with the weakest hand first and totals each bid times its rank
+fn rank_and_score(bids: &mut [(Hand, u64)]) -> u64 {
+    bids.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
     bids.iter()
         .enumerate()
         .map(|(i, (_, bid))| bid * (i as u64 + 1))
         .sum()
 }
 
-fn day7_parse(reader: impl BufRead) -> Vec<(Hand, u64)> {
-    // awww yisss, single iter chain parser
-    // shout out to itertools, I just found you and I already love you
-    reader
-        .lines()
-        .map(|x| x.unwrap())
-        .flat_map(|x| x.split_whitespace().map(|x| x.to_owned()).collect_vec())
-        .tuples()
-        .map(|(hand, bid)| (Hand::try_from(hand.as_str()).unwrap(), bid.parse().unwrap()))
+fn day7_parse(reader: impl BufRead, rules: &CamelRules) -> Result<Vec<(Hand, u64)>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::lines(&input)
+        .map(|line| {
+            let (hand, bid) = line
+                .words()
+                .collect_tuple()
+                .ok_or_else(|| line.error("expected a hand and a bid"))?;
+
+            let cards = rules.hand(hand.text).map_err(|e| hand.error(e))?;
+            Ok((cards, bid.parse()?))
+        })
         .collect()
 }
 
-#[derive(PartialEq, Eq)]
-struct Hand {
-    cards: [u8; 5],
-    strength: u8,
+/// Which cards are in play, how they rank and which are wild
+struct CamelRules {
+    // every card, weakest first
+    ranking: Vec<char>,
+    wilds: Vec<char>,
 }
 
-impl Hand {
-    fn strength(cards: &[u8; 5]) -> u8 {
-        let mut freq = cards
-            .iter()
-            // build hashmap with entry frequencies
-            .counts()
-            .into_iter()
-            // convert key and count to both be u8
-            .map(|(key, count)| (*key, count as u8))
-            // sort by frequency
-            .sorted_unstable_by(|a, b| a.1.cmp(&b.1).reverse())
-            .collect_vec();
-
-        // process wilds
-        let mut j = 0;
-        for card in freq.iter_mut() {
-            // found joker freqency
-            if card.0 == 0 {
-                j = card.1;
-                card.1 = 0;
-                break;
-            }
-        }
-
-        // re sort with wild frequency set to 0
-        freq.sort_unstable_by(|a, b| a.1.cmp(&b.1).reverse());
-        // add wild frequency to most frequent card
-        freq[0].1 += j;
-
-        // find hand strength
-        match freq[0].1 {
-            5 => 6, // five of a kind
-            4 => 5, // four of a kind
-            3 => match freq[1].1 {
-                2 => 4, // full house
-                1 => 3, // three of a kind
-                _ => unreachable!(),
-            },
-            2 => match freq[1].1 {
-                2 => 2, // two pair
-                1 => 1, // one pair
-                _ => unreachable!(),
-            },
-            1 => 0, // high card
-            _ => unreachable!(),
+impl CamelRules {
+    fn new(ranking: &str, wilds: &str) -> CamelRules {
+        CamelRules {
+            ranking: ranking.chars().collect(),
+            wilds: wilds.chars().collect(),
         }
     }
-}
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    // J is a jack
+    fn standard() -> CamelRules {
+        CamelRules::new("23456789TJQKA", "")
     }
-}
 
-// Sort hands first by strength, then by individual card strength
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let mut result = self.strength.cmp(&other.strength);
+    // J is a wild joker, weaker than every other card
+    fn jokers() -> CamelRules {
+        CamelRules::new("J23456789TQKA", "J")
+    }
 
-        for (card, other_card) in self.cards.iter().zip(other.cards.iter()) {
-            if result != Ordering::Equal {
-                break;
-            }
+    fn hand(&self, text: &str) -> Result<Hand, &'static str> {
+        let cards: Vec<char> = text.chars().collect();
+        let cards: [char; 5] = cards.try_into().map_err(|_| "Hands must be 5 cards")?;
 
-            result = card.cmp(other_card);
+        let mut values = [0; 5];
+        for (value, card) in values.iter_mut().zip(cards) {
+            *value = self
+                .ranking
+                .iter()
+                .position(|x| *x == card)
+                .ok_or("Unexpected character")? as u8;
         }
 
-        result
+        Ok(Hand {
+            hand_type: self.hand_type(&cards),
+            cards: values,
+        })
     }
-}
 
-impl TryFrom<&str> for Hand {
-    type Error = &'static str;
+    fn hand_type(&self, cards: &[char; 5]) -> HandType {
+        let wild = cards.iter().filter(|x| self.wilds.contains(x)).count();
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let cards: Vec<u8> = value
-            .chars()
-            .map(|c| match c {
-                '2' => Ok(2),
-                '3' => Ok(3),
-                '4' => Ok(4),
-                '5' => Ok(5),
-                '6' => Ok(6),
-                '7' => Ok(7),
-                '8' => Ok(8),
-                '9' => Ok(9),
-                'T' => Ok(10),
-                // J's are wild, score lower than every other card
-                'J' => Ok(0),
-                'Q' => Ok(12),
-                'K' => Ok(13),
-                'A' => Ok(14),
-                _ => Err("Unexpected character"),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        // frequency of each natural card, most frequent first
+        let mut freq = cards
+            .iter()
+            .filter(|x| !self.wilds.contains(x))
+            .counts()
+            .into_values()
+            .sorted_unstable()
+            .rev()
+            .collect_vec();
 
-        if cards.len() != 5 {
-            return Err("Hands must be 5 cards");
+        // wilds always do best by joining the most frequent card
+        match freq.first_mut() {
+            Some(most) => *most += wild,
+            None => freq.push(wild),
         }
 
-        let cards = cards.try_into().unwrap();
-
-        Ok(Hand {
-            strength: Hand::strength(&cards),
-            cards,
-        })
+        match (freq[0], freq.get(1)) {
+            (5, _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, Some(2)) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, Some(2)) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+// Sort hands first by type, then by individual card rank
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Hand {
+    hand_type: HandType,
+    // rank of each card under the rules it was dealt with
+    cards: [u8; 5],
+}
+
 fn day6() {
     let reader = BufReader::new(File::open("./input/2023/day6").unwrap());
-    let races = day6_parse(reader);
+    let races = day6_parse(reader).unwrap();
     let p1: u64 = races
         .iter()
         .map(|race| race.ways_to_win().unwrap())
@@ -853,29 +809,34 @@ fn day6() {
     println!("Part 2: {}", p2_w2win);
 }
 
-fn day6_parse(reader: impl BufRead) -> Vec<Race> {
-    let mut lines = reader.lines().map(|x| x.unwrap());
+fn day6_parse(reader: impl BufRead) -> Result<Vec<Race>, ParseError> {
+    let input = parse::read_input(reader)?;
+    let mut lines = parse::lines(&input);
 
-    let times: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|x| x.parse().unwrap())
-        .collect();
-    let records: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|x| x.parse().unwrap())
-        .collect();
+    let mut parse_row = |number: usize, name: &str| -> Result<Vec<u64>, ParseError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::new(number, 1, format!("missing {} line", name)))?;
+        let (key, values) = line.key_values()?;
+        if key.text != name {
+            return Err(key.error(format!("expected `{}`", name)));
+        }
+
+        values.tokens()
+    };
+
+    let times = parse_row(1, "Time")?;
+    let records = parse_row(2, "Distance")?;
+
+    if times.len() != records.len() {
+        return Err(ParseError::new(2, 1, "expected a record for every race"));
+    }
 
-    times
+    Ok(times
         .into_iter()
-        .zip(records.into_iter())
+        .zip(records)
         .map(|(time, record)| Race::new(time, record))
-        .collect()
+        .collect())
 }
 
 struct Race {
@@ -888,145 +849,88 @@ impl Race {
         Race { time, record }
     }
 
-    pub fn ways_to_win(&self) -> Option<u64> {
-        if let Some((lower, upper)) = self.record_button_hold_times() {
-            let mut exact_count = 0;
-            if lower.ceil() == lower {
-                exact_count += 1;
-            }
-            if upper.floor() == upper {
-                exact_count += 1;
-            }
+    pub fn ways_to_win(&self) -> Result<u64, QuadraticError> {
+        let hold_times = self.record_button_hold_times()?;
 
-            Some(upper.floor() as u64 - lower.ceil() as u64 + 1 - exact_count)
-        } else {
-            None
-        }
+        // at most time + 1 hold times, so this fits in a u64
+        Ok(hold_times.len() as u64)
     }
 
-    pub fn record_button_hold_times(&self) -> Option<(f64, f64)> {
-        let time = self.time as f64;
-        let record = self.record as f64;
-
-        // half of quadratic formula
-        let upper = (-time - (time.powi(2) - 4. * record).sqrt()) / -2.;
-        // other half of quadratic formula
-        let lower = (-time + (time.powi(2) - 4. * record).sqrt()) / -2.;
-
-        if upper.is_nan() || lower.is_nan() {
-            None
-        } else {
-            Some((lower, upper))
-        }
+    // every hold time which beats the record, distance = hold * (time - hold)
+    pub fn record_button_hold_times(&self) -> Result<Interval<u128>, QuadraticError> {
+        quadratic::negative_interval(self.time as u128, self.record as u128)
     }
 }
 
 fn day5() {
     let reader = BufReader::new(File::open("./input/2023/day5").unwrap());
-    let mut almanic = day5_parse(reader);
+    let almanic = day5_parse(reader).unwrap();
     let mut locations: Vec<u64> = almanic.find_locations_p1();
     locations.sort_unstable();
     println!("Nearest Location Part 1: {}", locations.first().unwrap());
 
-    let mut location_ranges = almanic.find_locations();
-    location_ranges.sort_unstable_by(|span1, span2| span1.start.cmp(&span2.start));
+    let nearest = almanic.find_locations().min().unwrap();
+    println!("Nearest Location Part 2: {}", nearest);
     println!(
-        "Nearest Location Part 2: {}",
-        location_ranges.first().unwrap().start
+        "Seeds for nearest location: {:?}",
+        almanic.seeds_for_location(nearest)
     );
 }
 
-fn day5_parse(reader: impl BufRead) -> Almanac {
-    let mut line_iter = reader.lines().map(|x| x.unwrap());
+fn day5_parse(reader: impl BufRead) -> Result<Almanac, ParseError> {
+    let input = parse::read_input(reader)?;
+    let sections = parse::sections(&input);
 
     // parse seeds
-    let seeds: Vec<u64> = line_iter
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .filter_map(|seed| seed.parse().ok())
-        .collect();
-    // skip first empty line
-    line_iter.next();
-
-    let mut mappings: Vec<Mapping> = Vec::new();
-
-    while let Some(line) = line_iter.next() {
-        // look for mapping headers
-        let mut mapping = line.split(' ').next().unwrap().split('-');
-        let from: String = mapping.next().unwrap().to_owned();
-        mapping.next();
-        let to: String = mapping.next().unwrap().to_owned();
-
-        let mapping = day5_parse_mappings(&mut line_iter, from, to);
-        mappings.push(mapping);
-    }
-
-    Almanac { seeds, mappings }
-}
-
-fn day5_parse_mappings<T>(line_iter: &mut T, from: String, to: String) -> Mapping
-where
-    T: Iterator<Item = String>,
-{
-    let mut mappings: Vec<[u64; 3]> = Vec::new();
-    // look for mappings
-    while let Some(line) = line_iter.next() {
-        // found end of current mapping, break to outer loop
-        if line.is_empty() {
-            break;
-        }
-
-        // parse the integers from the line into an arrayinto an array
-        let map_range: [u64; 3] = line
-            .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect::<Vec<u64>>()
-            .try_into()
-            .unwrap_or_else(|_| panic!("expected 3 numbers per line\n{}", line));
-        mappings.push(map_range);
-    }
-
-    Mapping {
-        from,
-        to,
-        mappings,
-        sorted: false,
-    }
-}
+    let (seeds, mappings) = sections
+        .split_first()
+        .ok_or_else(|| ParseError::new(1, 1, "missing seeds"))?;
+    let line = seeds[0];
+    let (key, values) = line.key_values()?;
+    if key.text != "seeds" {
+        return Err(key.error("expected `seeds`"));
+    }
+    let seeds: Vec<u64> = values.tokens()?;
+    // part 2 reads the seeds as pairs of start and length
+    if !seeds.len().is_multiple_of(2) {
+        let last = values.words().last().unwrap();
+        return Err(last.error("seeds have to come in pairs of start and length"));
+    }
+
+    let mappings = mappings
+        .iter()
+        .map(|section| day5_parse_mappings(section))
+        .collect::<Result<_, _>>()?;
 
-struct Span {
-    pub start: u64,
-    pub range: u64,
+    Ok(Almanac { seeds, mappings })
 }
 
-impl Span {
-    pub fn new(start: u64, range: u64) -> Span {
-        Span { start, range }
-    }
-
-    // splits this span
-    // panics if impossible to create two valid spans
-    pub fn split_at(&mut self, at: u64) -> Span {
-        if at <= self.start || at >= self.start + self.range {
-            panic!("Unable to split into two valid spans");
-        }
-
-        let old_range = at - self.start;
-        let new_range = self.range - old_range;
-
-        self.range = old_range;
+fn day5_parse_mappings(section: &[Line]) -> Result<Mapping, ParseError> {
+    // look for mapping headers
+    let header = section[0];
+    let (name, _) = header.key_values()?;
+    let (from, to) = name
+        .text
+        .strip_suffix(" map")
+        .and_then(|x| x.split_once("-to-"))
+        .ok_or_else(|| name.error("expected `<from>-to-<to> map:`"))?;
 
-        Span {
-            start: at,
-            range: new_range,
-        }
-    }
+    let mut mappings: Vec<[u64; 3]> = section[1..]
+        .iter()
+        .map(|line| {
+            // parse the integers from the line into an array
+            line.tokens::<u64>()?
+                .try_into()
+                .map_err(|_| line.error_at(0, "expected 3 numbers per line"))
+        })
+        .collect::<Result<_, _>>()?;
+    mappings.sort_unstable_by_key(|x| x[1]);
 
-    #[allow(dead_code)]
-    pub fn to_string(&self) -> String {
-        format!("{}--{}", self.start, self.start + self.range)
-    }
+    Ok(Mapping {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        mappings,
+    })
 }
 
 #[allow(dead_code)]
@@ -1036,52 +940,54 @@ struct Almanac {
 }
 
 impl Almanac {
-    pub fn find_locations(&mut self) -> Vec<Span> {
-        // build process queue, starting by interpreting seeds as spans
-        let mut process_queue: Vec<(usize, Span)> = self
-            .seeds
-            .chunks(2)
-            .map(|slice| (0, Span::new(slice[0], slice[1])))
-            .collect();
+    pub fn find_locations(&self) -> RangeSet<u64> {
+        // interpret seeds as ranges
+        let ranges = self.seed_ranges();
+        assert!(!ranges.is_empty());
 
-        assert!(!process_queue.is_empty());
+        self.seed_to_location().map_range(&ranges)
+    }
 
-        let mut results = Vec::new();
-        while let Some((index, span)) = process_queue.pop() {
-            // span was mapped through last mapping
-            // add to results
-            if index == self.mappings.len() {
-                results.push(span);
-                continue;
-            }
+    pub fn find_locations_p1(&self) -> Vec<u64> {
+        let mapping = self.seed_to_location();
+        self.seeds.iter().map(|seed| mapping.map(*seed)).collect()
+    }
 
-            // map current span, push resulting spans to process queue
-            let mapped = self.mappings[index].map_range(span);
-            process_queue.extend(mapped.into_iter().map(|x| (index + 1, x)));
-        }
+    // finds every seed, within the seed ranges, that ends up at location
+    pub fn seeds_for_location(&self, location: u64) -> Vec<u64> {
+        let ranges = self.seed_ranges();
+
+        self.seed_to_location()
+            .preimage(location)
+            .into_iter()
+            .filter(|seed| ranges.contains(*seed))
+            .collect()
+    }
+
+    // collapses the chain of mappings into a single mapping
+    pub fn seed_to_location(&self) -> Mapping {
+        let mut mappings = self.mappings.iter();
+        let first = mappings.next().expect("almanac has no mappings").clone();
 
-        results
+        mappings.fold(first, |composed, mapping| composed.compose(mapping))
     }
-    pub fn find_locations_p1(&mut self) -> Vec<u64> {
+
+    fn seed_ranges(&self) -> RangeSet<u64> {
         self.seeds
             .iter()
-            .map(|seed| {
-                let mut input: u64 = *seed;
-                for map in self.mappings.iter_mut() {
-                    input = map.map(input);
-                }
-                input
-            })
+            .tuples()
+            .map(|(start, len)| Interval::with_len(*start, *len))
             .collect()
     }
 }
 
+// ranges are stored as [to, from, range] and kept sorted by from
 #[allow(dead_code)]
+#[derive(Debug, Clone)]
 struct Mapping {
     from: String,
     to: String,
     mappings: Vec<[u64; 3]>,
-    sorted: bool,
 }
 
 #[allow(dead_code)]
@@ -1091,593 +997,699 @@ impl Mapping {
             from,
             to,
             mappings: Vec::new(),
-            sorted: true,
         }
     }
 
     pub fn add_range(&mut self, from_num: u64, to_num: u64, range: u64) {
-        self.sorted = false;
-        self.mappings.push([to_num, from_num, range]);
+        let index = self.mappings.partition_point(|x| x[1] < from_num);
+        self.mappings.insert(index, [to_num, from_num, range]);
     }
 
-    pub fn map(&mut self, from: u64) -> u64 {
-        self.sort_mappings();
+    pub fn map(&self, from: u64) -> u64 {
+        // binary search for the last range starting at or before from
+        let index = self.mappings.partition_point(|x| x[1] <= from);
 
-        // iterate over mappings looking for matching range, return "from" if no mapping found
-        for mapping in self.mappings.iter() {
-            // sorted mappings, so no mappings found further along
-            if mapping[1] > from {
-                break;
-            }
+        match index.checked_sub(1).map(|i| self.mappings[i]) {
+            Some(mapping) if from - mapping[1] < mapping[2] => mapping[0] + (from - mapping[1]),
+            // no mapping found, map implicitly
+            _ => from,
+        }
+    }
 
-            if mapping[1] <= from && (mapping[1] + mapping[2]) > from {
-                let offset = from - mapping[1];
+    pub fn map_range(&self, from: &RangeSet<u64>) -> RangeSet<u64> {
+        let mut to = RangeSet::new();
 
-                return mapping[0] + offset;
+        for interval in from.iter() {
+            // ranges are sorted and don't overlap, so skip those ending before the interval
+            let first = self
+                .mappings
+                .partition_point(|x| x[1] + x[2] <= interval.start());
+            let mut next_start = interval.start();
+
+            for range in self.mappings[first..]
+                .iter()
+                .take_while(|x| x[1] < interval.end())
+            {
+                let source = Interval::with_len(range[1], range[2]);
+                let Some(piece) = interval.intersection(&source) else {
+                    continue;
+                };
+
+                // implicitly map the gap before this range
+                if next_start < piece.start() {
+                    to.insert(Interval::new(next_start, piece.start()));
+                }
+                let offset = piece.start() - range[1];
+                to.insert(Interval::with_len(range[0] + offset, piece.len()));
+                next_start = piece.end();
+            }
+
+            if next_start < interval.end() {
+                to.insert(Interval::new(next_start, interval.end()));
             }
         }
-        from
+
+        to
     }
 
-    pub fn map_range(&mut self, from: Span) -> Vec<Span> {
-        self.sort_mappings();
-        let mut to = Vec::new();
-
-        let mut span_opt = Some(from);
-        for range in self.mappings.iter() {
-            let mut span = span_opt.take().unwrap();
-            // span above map range, gg go next
-            if range[1] + range[2] <= span.start {
-                span_opt = Some(span);
-                continue;
-            // span below map range, no explicit mappings found, break
-            } else if span.start + span.range <= range[1] {
-                span_opt = Some(span);
-                break;
-            }
+    /// Builds a single mapping equivalent to applying self and then next
+    ///
+    /// the pieces of self are visited in order of where they map to, so the
+    /// first piece of next that can overlap them only ever moves forward
+    pub fn compose(&self, next: &Mapping) -> Mapping {
+        let next_pieces = next.pieces();
+        let mut pieces = self.pieces();
+        pieces.sort_unstable_by_key(|(_, dest)| *dest);
 
-            // check for span area below current range and map it implicitly
-            if span.start < range[1] {
-                let above = span.split_at(range[1]);
-                to.push(span);
-                span = above;
-            }
+        let mut cursor = 0;
+        let mut mappings: Vec<[u64; 3]> = Vec::new();
 
-            let mut next = None;
-            // check for span area above current range and save it to for next iteration
-            if span.start + span.range > range[1] + range[2] {
-                next = Some(span.split_at(range[1] + range[2]));
+        for (source, dest) in pieces {
+            let image = Interval::with_len(dest, source.len());
+            while cursor < next_pieces.len() && next_pieces[cursor].0.end() <= image.start() {
+                cursor += 1;
             }
 
-            // map span and push to results
-            let offset = span.start - range[1];
-            span.start = range[0] + offset;
-            to.push(span);
-
-            // still more span to map, continue
-            if let Some(span) = next {
-                span_opt = Some(span);
-                continue;
-            // no more mapping to do, we're done
-            } else {
-                break;
+            // split the image of this piece by the pieces of next
+            let overlapping = next_pieces[cursor..]
+                .iter()
+                .take_while(|(next_source, _)| next_source.start() < image.end());
+
+            for (next_source, next_dest) in overlapping {
+                let Some(piece) = image.intersection(next_source) else {
+                    continue;
+                };
+                let from = source.start() + (piece.start() - dest);
+                let to = next_dest + (piece.start() - next_source.start());
+
+                // implicit mappings don't need to be stored
+                if from != to {
+                    mappings.push([to, from, piece.len()]);
+                }
             }
         }
 
-        // implicitly map any remaining span
-        if let Some(span) = span_opt {
-            to.push(span);
+        // back in order of source, joining ranges which continue each other
+        mappings.sort_unstable_by_key(|x| x[1]);
+        let mappings = mappings
+            .into_iter()
+            .fold(Vec::new(), |mut joined: Vec<[u64; 3]>, x| {
+                match joined.last_mut() {
+                    Some(last) if last[1] + last[2] == x[1] && last[0] + last[2] == x[0] => {
+                        last[2] += x[2];
+                    }
+                    _ => joined.push(x),
+                }
+                joined
+            });
+
+        Mapping {
+            from: self.from.clone(),
+            to: next.to.clone(),
+            mappings,
+        }
+    }
+
+    // finds every value which maps to the value to
+    pub fn preimage(&self, to: u64) -> Vec<u64> {
+        let mut from = self
+            .mappings
+            .iter()
+            .filter(|x| x[0] <= to && to - x[0] < x[2])
+            .map(|x| x[1] + (to - x[0]))
+            .collect_vec();
+
+        // values outside every range map to themselves
+        let covered = self.mappings.iter().any(|x| x[1] <= to && to - x[1] < x[2]);
+        if !covered {
+            from.push(to);
         }
 
-        to
+        from.sort_unstable();
+        from
     }
 
-    /// sort by from range then range
-    fn sort_mappings(&mut self) {
-        // sort if not sorted
-        if !self.sorted {
-            self.mappings.sort_unstable_by(|map1, map2| {
-                if map1[1] == map2[1] {
-                    map1[2].cmp(&map2[2])
-                } else {
-                    map1[1].cmp(&map2[1])
-                }
-            });
-            self.sorted = true;
+    // the reverse mapping, only exists when every value has exactly one preimage
+    pub fn inverse(&self) -> Option<Mapping> {
+        let mut mappings = self
+            .mappings
+            .iter()
+            .map(|x| [x[1], x[0], x[2]])
+            .collect_vec();
+        mappings.sort_unstable_by_key(|x| x[1]);
+
+        // destination ranges can't overlap each other
+        if mappings
+            .iter()
+            .tuple_windows()
+            .any(|(a, b)| a[1] + a[2] > b[1])
+        {
+            return None;
         }
+
+        // ranges must cover the values they map onto, so implicit mappings stay one to one
+        let sources: RangeSet<u64> = self
+            .mappings
+            .iter()
+            .map(|x| Interval::with_len(x[1], x[2]))
+            .collect();
+        let dests: RangeSet<u64> = self
+            .mappings
+            .iter()
+            .map(|x| Interval::with_len(x[0], x[2]))
+            .collect();
+        if sources != dests {
+            return None;
+        }
+
+        Some(Mapping {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            mappings,
+        })
+    }
+
+    // splits every possible input into (from, to start) pieces, including implicit ones
+    fn pieces(&self) -> Vec<(Interval<u64>, u64)> {
+        let mut pieces = Vec::new();
+        let mut next_start = 0;
+
+        // empty ranges don't map anything
+        for range in self.mappings.iter().filter(|x| x[2] > 0) {
+            if next_start < range[1] {
+                pieces.push((Interval::new(next_start, range[1]), next_start));
+            }
+            pieces.push((Interval::with_len(range[1], range[2]), range[0]));
+            next_start = range[1] + range[2];
+        }
+
+        if next_start < u64::MAX {
+            pieces.push((Interval::new(next_start, u64::MAX), next_start));
+        }
+
+        pieces
     }
 }
 
 fn day4() {
     let reader = BufReader::new(File::open("./input/2023/day4").unwrap());
-    let cards = day4_parser(reader);
+    let cards = day4_parser(reader).unwrap();
 
     println!("Part 1: {}", day4p1_logic(&cards));
-    println!("Part 2: {}", day4p2_logic(cards));
+    println!("Part 2: {}", day4p2_logic(&cards));
 }
 
-fn day4p2_logic(mut cards: Vec<Card>) -> usize {
-    for i in 0..cards.len() {
-        let wins = cards[i].wins();
-        for j in (i + 1)..(i + 1 + wins) {
-            cards[j].copies += cards[i].copies;
-        }
-    }
-
-    cards.into_iter().map(|card| card.copies).sum()
+fn day4p1_logic(cards: &Scratchcards) -> u64 {
+    cards.points(Scoring::Doubling)
 }
 
-fn day4p1_logic(cards: &Vec<Card>) -> usize {
-    cards.iter().map(|card| card.compute_points()).sum()
+fn day4p2_logic(cards: &Scratchcards) -> u64 {
+    cards.copies().iter().sum()
 }
 
-fn day4_parser(reader: impl BufRead) -> Vec<Card> {
-    let mut cards: Vec<Card> = Vec::new();
+fn day4_parser(reader: impl BufRead) -> Result<Scratchcards, ParseError> {
+    let input = parse::read_input(reader)?;
 
-    for line in reader.lines().map(|x| x.unwrap()) {
-        // skip the card id
-        let mut tokens_iter = line.split_whitespace().skip(2);
+    let cards = parse::lines(&input)
+        .map(|line| {
+            let (name, numbers) = line.key_values()?;
+            let id = name
+                .strip_prefix("Card")
+                .ok_or_else(|| name.error("expected `Card <id>`"))?;
+            let (winning, have) = numbers.split_once('|')?;
+
+            Ok(Card {
+                id: id.trim().parse()?,
+                winning: winning.tokens::<u64>()?.into_iter().collect(),
+                have: have.tokens()?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
 
-        // parse the winning numbers
-        let mut card = Card::new();
-        while let Some(token) = tokens_iter.next() {
-            // end of winning numbers
-            if token == "|" {
-                break;
-            }
-            card.winning.insert(token.parse().unwrap());
-        }
+    Ok(Scratchcards::new(cards))
+}
 
-        // parse the numbers this card has
-        while let Some(token) = tokens_iter.next() {
-            card.have.push(token.parse().unwrap());
+/// How many points a card is worth for its number of matches
+#[derive(Debug, Clone, Copy)]
+enum Scoring {
+    // one point for the first match, doubled for every match after it
+    Doubling,
+    // one point for every match
+    #[allow(dead_code)]
+    Linear,
+    #[allow(dead_code)]
+    Custom(fn(usize) -> u64),
+}
+
+impl Scoring {
+    // doubling saturates at u64::MAX from 65 wins on
+    pub fn score(&self, wins: usize) -> u64 {
+        match self {
+            Scoring::Doubling if wins == 0 => 0,
+            Scoring::Doubling => u32::try_from(wins - 1)
+                .ok()
+                .and_then(|shift| 1u64.checked_shl(shift))
+                .unwrap_or(u64::MAX),
+            Scoring::Linear => wins as u64,
+            Scoring::Custom(score) => score(wins),
         }
-
-        cards.push(card);
     }
-    cards
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Card {
-    pub copies: usize,
-    pub have: Vec<u64>,
-    pub winning: HashSet<u64>,
+    id: u64,
+    winning: HashSet<u64>,
+    have: Vec<u64>,
 }
 
 impl Card {
-    pub fn new() -> Self {
-        Card {
-            copies: 1,
-            have: Vec::new(),
-            winning: HashSet::new(),
+    pub fn wins(&self) -> usize {
+        self.have
+            .iter()
+            .filter(|num| self.winning.contains(num))
+            .count()
+    }
+}
+
+/// Where the copies of a card came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CopyBreakdown {
+    id: u64,
+    copies: u64,
+    // ids of the earlier cards and how many copies each of them won
+    sources: Vec<(u64, u64)>,
+}
+
+impl fmt::Display for CopyBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Card {}: {} copies (1 original", self.id, self.copies)?;
+        for (id, copies) in self.sources.iter() {
+            write!(f, ", {} from card {}", copies, id)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A pile of scratchcards, where every match wins a copy of a later card
+struct Scratchcards {
+    cards: Vec<Card>,
+    wins: Vec<usize>,
+}
+
+impl Scratchcards {
+    pub fn new(cards: Vec<Card>) -> Scratchcards {
+        let wins = cards.iter().map(Card::wins).collect();
+        Scratchcards { cards, wins }
+    }
+
+    // total points, saturating like the scores themselves
+    pub fn points(&self, scoring: Scoring) -> u64 {
+        self.wins
+            .iter()
+            .map(|wins| scoring.score(*wins))
+            .fold(0, u64::saturating_add)
+    }
+
+    // the cards each card wins copies of, stopping at the end of the pile
+    fn won_range(&self, card: usize) -> Range<usize> {
+        (card + 1)..(card + 1 + self.wins[card]).min(self.cards.len())
+    }
+
+    /// Number of copies of every card, including the original
+    ///
+    /// each card adds its copies to a range of later cards, so instead of
+    /// adding to every card in the range the total is only changed where a
+    /// range starts and where it ends
+    pub fn copies(&self) -> Vec<u64> {
+        let mut copies = Vec::with_capacity(self.cards.len());
+        let mut won = 0;
+        let mut ending = vec![0; self.cards.len() + 1];
+
+        for card in 0..self.cards.len() {
+            won -= ending[card];
+            let count = 1 + won;
+            copies.push(count);
+
+            let range = self.won_range(card);
+            if !range.is_empty() {
+                won += count;
+                ending[range.end] += count;
+            }
         }
-    }
 
-    pub fn compute_points(&self) -> usize {
-        let wins = self.wins();
-        if wins == 0 {
-            0
-        } else {
-            1 << wins - 1
-        }
+        copies
     }
 
-    pub fn wins(&self) -> usize {
-        self.have
+    #[allow(dead_code)]
+    pub fn breakdown(&self) -> Vec<CopyBreakdown> {
+        let copies = self.copies();
+        let mut breakdown: Vec<_> = self
+            .cards
             .iter()
-            .filter(|num| self.winning.contains(num))
-            .count()
+            .zip(copies.iter())
+            .map(|(card, copies)| CopyBreakdown {
+                id: card.id,
+                copies: *copies,
+                sources: Vec::new(),
+            })
+            .collect();
+
+        for (i, (card, copies)) in self.cards.iter().zip(copies).enumerate() {
+            for won in self.won_range(i) {
+                breakdown[won].sources.push((card.id, copies));
+            }
+        }
+
+        breakdown
     }
 }
 
 fn day3() {
     let reader = BufReader::new(File::open("./input/2023/day3").unwrap());
-    let result = day3p2_logic(reader);
-    println!("{}", result);
-}
-
-fn day3p2_logic(reader: impl BufRead) -> u64 {
-    // figure out line length
-    let mut reader_iter = reader.lines().peekable();
-    let line_length = reader_iter.peek().unwrap().as_ref().unwrap().len();
-    let reader_iter = reader_iter.map(|x| x.unwrap());
+    let schematic = day3_parse(reader).unwrap();
 
-    // set up dummy lines to be referenced at the beginning and end of iteration
-    let prev_line: String = iter::repeat('.').take(line_length).collect();
-    let last_line = vec![prev_line.clone()];
+    println!("Part 1: {}", day3p1_logic(&schematic));
+    println!("Part 2: {}", day3p2_logic(&schematic));
+}
 
-    let mut reader_iter = reader_iter.chain(last_line.into_iter());
-    let current_line = reader_iter.next().unwrap();
+fn day3p1_logic(schematic: &Schematic) -> u64 {
+    schematic.part_numbers().map(|x| x.value).sum()
+}
 
-    let mut gear_ratios: Vec<HashMap<usize, (u64, u32)>> = Vec::new();
-    gear_ratios.push(HashMap::new());
+fn day3p2_logic(schematic: &Schematic) -> u64 {
+    schematic.gears().iter().map(Gear::ratio).sum()
+}
 
-    let mut prev: Vec<char> = prev_line.chars().collect();
-    let mut current: Vec<char> = current_line.chars().collect();
+fn day3_parse(reader: impl BufRead) -> Result<Schematic, ParseError> {
+    let input = parse::read_input(reader)?;
+    let grid = Grid::parse(&input, |c| match c {
+        '.' => Ok(Cell::Empty),
+        '0'..='9' => Ok(Cell::Digit(c as u8 - b'0')),
+        c if c.is_ascii_punctuation() => Ok(Cell::Symbol(c)),
+        _ => Err(format!("invalid character `{}`", c)),
+    })?;
 
-    let mut line_num = 0;
+    Ok(Schematic::new(grid))
+}
 
-    for next_line in reader_iter {
-        let mut next: Vec<char> = next_line.chars().collect();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Digit(u8),
+    Symbol(char),
+}
 
-        gear_ratios.push(HashMap::new());
-        // figure out which numbers to are to be included
-        day3p2_find_adjascent_parts(
-            &mut prev,
-            &mut current,
-            &mut next,
-            &mut gear_ratios,
-            line_num,
-        );
+/// A number in the schematic, a part number if any symbol touches it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PartNumber {
+    value: u64,
+    row: usize,
+    columns: Interval<usize>,
+    // positions of the symbols touching the number
+    symbols: Vec<(usize, usize)>,
+}
 
-        prev = current;
-        current = next;
-        line_num += 1;
+impl PartNumber {
+    pub fn is_part(&self) -> bool {
+        !self.symbols.is_empty()
     }
-
-    gear_ratios
-        .into_iter()
-        .map(|hashmap| {
-            let mut sum = 0;
-            for (_key, (value, count)) in hashmap {
-                if count == 2 {
-                    sum += value;
-                }
-            }
-
-            sum
-        })
-        .sum()
 }
 
-fn day3p2_find_adjascent_parts(
-    prev: &mut [char],
-    current: &mut [char],
-    next: &mut [char],
-    gears: &mut Vec<HashMap<usize, (u64, u32)>>,
-    line_num: usize,
-) {
-    let mut digits: Vec<char> = Vec::new();
-
-    for i in 0..current.len() {
-        if current[i].is_ascii_digit() {
-            digits.push(current[i]);
-        // found end of number
-        } else if !digits.is_empty() {
-            let value: String = digits.iter().collect();
-            let value: u64 = value.parse().unwrap();
-
-            day3p2_check_symbol_range(
-                i - digits.len(),
-                i,
-                [prev, current, next],
-                gears,
-                value,
-                line_num,
-            );
-
-            digits.clear();
-        }
-
-        // found end of line
-        if i == current.len() - 1 && !digits.is_empty() {
-            let value: String = digits.iter().collect();
-            let value: u64 = value.parse().unwrap();
-
-            day3p2_check_symbol_range(
-                i - digits.len() + 1,
-                i,
-                [prev, current, next],
-                gears,
-                value,
-                line_num,
-            );
-
-            digits.clear();
-        }
-    }
+/// A symbol with the numbers touching it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Gear {
+    position: (usize, usize),
+    symbol: char,
+    parts: Vec<u64>,
 }
 
-fn day3p2_check_symbol_range(
-    from: usize,
-    mut to: usize,
-    lines: [&mut [char]; 3],
-    gears: &mut Vec<HashMap<usize, (u64, u32)>>,
-    value: u64,
-    line_num: usize,
-) {
-    // extend from and to by 1 if not at bounds
-    let from = if from > 0 { from - 1 } else { from };
-    to += 1;
-
-    for offset in 0..lines.len() {
-        for i in from..to {
-            if lines[offset][i] == '*' {
-                gears[line_num + offset - 1]
-                    .entry(i)
-                    .and_modify(|(val, count)| {
-                        *val *= value;
-                        *count += 1;
-                    })
-                    .or_insert((value, 1));
-            }
-        }
+impl Gear {
+    pub fn ratio(&self) -> u64 {
+        self.parts.iter().product()
     }
 }
-fn _day3p1_logic(reader: impl BufRead) -> u64 {
-    // figure out line length
-    let mut reader_iter = reader.lines().peekable();
-    let line_length = reader_iter.peek().unwrap().as_ref().unwrap().len();
-    let reader_iter = reader_iter.map(|x| x.unwrap());
-
-    // set up dummy lines to be referenced at the beginning and end of iteration
-    let prev_line: String = iter::repeat('.').take(line_length).collect();
-    let last_line = vec![prev_line.clone()];
-
-    let mut reader_iter = reader_iter.chain(last_line.into_iter());
-    let current_line = reader_iter.next().unwrap();
-
-    let mut engine_parts: Vec<u64> = Vec::new();
 
-    let mut prev: Vec<char> = prev_line.chars().collect();
-    let mut current: Vec<char> = current_line.chars().collect();
+struct Schematic {
+    grid: Grid<Cell>,
+    numbers: Vec<PartNumber>,
+    // indices of the numbers touching each symbol
+    neighbours: HashMap<(usize, usize), Vec<usize>>,
+}
 
-    for next_line in reader_iter {
-        let mut next: Vec<char> = next_line.chars().collect();
+impl Schematic {
+    // finds every number and the symbols around it in a single pass
+    pub fn new(grid: Grid<Cell>) -> Schematic {
+        let mut numbers = Vec::new();
+        let mut neighbours: HashMap<_, Vec<usize>> = HashMap::new();
 
-        // figure out which numbers to are to be included
-        _day3_find_adjascent_parts(&mut prev, &mut current, &mut next, &mut engine_parts);
+        for row in 0..grid.rows() {
+            let cells = grid.row(row);
+            let mut col = 0;
 
-        prev = current;
-        current = next;
-    }
+            while col < cells.len() {
+                if !matches!(cells[col], Cell::Digit(_)) {
+                    col += 1;
+                    continue;
+                }
 
-    engine_parts.into_iter().sum()
-}
+                let start = col;
+                let mut value = 0;
+                while let Some(Cell::Digit(digit)) = cells.get(col) {
+                    value = value * 10 + *digit as u64;
+                    col += 1;
+                }
 
-fn _day3_find_adjascent_parts(
-    prev: &mut [char],
-    current: &mut [char],
-    next: &mut [char],
-    parts: &mut Vec<u64>,
-) {
-    let mut digits: Vec<char> = Vec::new();
+                let symbols: Vec<_> = (start..col)
+                    .flat_map(|x| grid.neighbours(row, x))
+                    .filter(|(r, c)| matches!(grid.get(*r, *c), Some(Cell::Symbol(_))))
+                    .unique()
+                    .collect();
 
-    for i in 0..current.len() {
-        if current[i].is_ascii_digit() {
-            digits.push(current[i]);
-        // found end of number
-        } else if !digits.is_empty() {
-            if _day3_check_symbol_range(i - digits.len(), i, [prev, current, next]) {
-                let parts_num: String = digits.iter().collect();
-                let parts_num: u64 = parts_num.parse().unwrap();
+                for symbol in symbols.iter() {
+                    neighbours.entry(*symbol).or_default().push(numbers.len());
+                }
 
-                parts.push(parts_num);
-                print!("\x1b[93m{}\x1b[0m", parts_num);
-            } else {
-                let str: String = digits.iter().collect();
-                print!("{}", str);
+                numbers.push(PartNumber {
+                    value,
+                    row,
+                    columns: Interval::new(start, col),
+                    symbols,
+                });
             }
-
-            digits.clear();
         }
 
-        if !current[i].is_ascii_digit() {
-            print!("{}", current[i])
+        Schematic {
+            grid,
+            numbers,
+            neighbours,
         }
+    }
 
-        // found end if line
-        if i == current.len() - 1 && !digits.is_empty() {
-            if _day3_check_symbol_range(i - digits.len() + 1, i, [prev, current, next]) {
-                let parts_num: String = digits.iter().collect();
-                let parts_num: u64 = parts_num.parse().unwrap();
-
-                parts.push(parts_num);
-                print!("\x1b[93m{}\x1b[0m", parts_num);
-            } else {
-                let str: String = digits.iter().collect();
-                print!("{}", str);
-            }
-            digits.clear();
-        }
+    // every number, including those without a symbol
+    #[allow(dead_code)]
+    pub fn numbers(&self) -> &[PartNumber] {
+        &self.numbers
     }
 
-    print!("\n");
-}
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers.iter().filter(|x| x.is_part())
+    }
 
-fn _day3_check_symbol_range(from: usize, mut to: usize, lines: [&mut [char]; 3]) -> bool {
-    // extend from and to by 1 if not at bounds
-    let from = if from > 0 { from - 1 } else { from };
-    to += 1;
+    /// Every `symbol` touching exactly `count` numbers, in reading order
+    pub fn symbols_with_neighbours(&self, symbol: char, count: usize) -> Vec<Gear> {
+        self.neighbours
+            .iter()
+            .filter(|(position, numbers)| {
+                numbers.len() == count
+                    && self.grid.get(position.0, position.1) == Some(&Cell::Symbol(symbol))
+            })
+            .map(|(position, numbers)| Gear {
+                position: *position,
+                symbol,
+                parts: numbers.iter().map(|x| self.numbers[*x].value).collect(),
+            })
+            .sorted_by_key(|x| x.position)
+            .collect()
+    }
 
-    for arr in lines {
-        for i in from..to {
-            if !(arr[i].is_ascii_digit()) && !(arr[i] == '.') {
-                return true;
-            }
-        }
+    // a gear is a `*` touching exactly two part numbers
+    pub fn gears(&self) -> Vec<Gear> {
+        self.symbols_with_neighbours('*', 2)
     }
-    false
 }
 
 fn day2() {
     let reader = BufReader::new(File::open("./input/2023/day2").unwrap());
-    let result = day2p2_logic(reader);
+    let games = day2_parse(reader).unwrap();
 
-    println!("result: {}", result);
+    println!("Part 1: {}", day2p1_logic(&games));
+    println!("Part 2: {}", day2p2_logic(&games));
 }
 
-fn _day2p1_logic(reader: impl BufRead) -> u64 {
-    let mut sum = 0;
+// sums the ids of games possible with 12 red, 13 green and 14 blue cubes
+fn day2p1_logic(games: &[Game]) -> u64 {
+    let bag = Draw::from_iter([("red", 12), ("green", 13), ("blue", 14)]);
 
-    for game in reader.lines().into_iter().map(|x| x.unwrap()) {
-        sum += _day2_is_game_possible(game);
-    }
+    games
+        .iter()
+        .filter(|game| game.is_possible(&bag))
+        .map(|game| game.id)
+        .sum()
+}
 
-    sum
+fn day2p2_logic(games: &[Game]) -> u64 {
+    games.iter().map(Game::power).sum()
 }
 
-fn day2p2_logic(reader: impl BufRead) -> u64 {
-    let mut sum = 0;
+fn day2_parse(reader: impl BufRead) -> Result<Vec<Game>, ParseError> {
+    let input = parse::read_input(reader)?;
 
-    for game in reader.lines().into_iter().map(|x| x.unwrap()) {
-        println!("{}", game);
-        let game_power = day2_game_power(game);
-        println!("{}", game_power);
-        sum += game_power;
-    }
+    parse::lines(&input)
+        .map(|line| {
+            let (name, draws) = line.key_values()?;
+            let id = name
+                .strip_prefix("Game ")
+                .ok_or_else(|| name.error("expected `Game <id>`"))?;
+
+            let draws = draws
+                .split(';')
+                .map(|draw| {
+                    draw.split(',')
+                        .map(|cubes| match cubes.words().collect_tuple() {
+                            Some((count, colour)) => Ok((colour.text, count.parse()?)),
+                            None => Err(cubes.trim().error("expected `<count> <colour>`")),
+                        })
+                        .collect::<Result<Draw, _>>()
+                })
+                .collect::<Result<_, _>>()?;
 
-    sum
+            Ok(Game {
+                id: id.parse()?,
+                draws,
+            })
+        })
+        .collect()
 }
 
-fn _day2_is_game_possible(game: String) -> u64 {
-    let max_red = 12;
-    let max_green = 13;
-    let max_blue = 14;
+/// A number of cubes of each colour, either a handful or the contents of a bag
+///
+/// colours are whatever names the input uses, missing colours count as 0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Draw {
+    cubes: BTreeMap<String, u64>,
+}
 
-    let mut split = game.split([' ', ':']);
-    // skip the text "Game"
-    split.next();
-    let game_id: u64 = split.next().unwrap().parse().unwrap();
-    // skip empty token
-    split.next();
+impl Draw {
+    pub fn get(&self, colour: &str) -> u64 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
 
-    while let Some(num) = split.next() {
-        let num: u64 = num.parse().unwrap();
-        let color = split.next().unwrap();
+    // determine whether every cube of other could have come from this
+    pub fn contains(&self, other: &Draw) -> bool {
+        other
+            .cubes
+            .iter()
+            .all(|(colour, count)| *count <= self.get(colour))
+    }
 
-        match color.chars().next().unwrap() {
-            'r' => {
-                if num > max_red {
-                    return 0;
-                }
-            }
-            'g' => {
-                if num > max_green {
-                    return 0;
-                }
-            }
-            'b' => {
-                if num > max_blue {
-                    return 0;
-                }
-            }
-            _ => {
-                panic!("unexpected input, expected a color name")
-            }
+    // the larger count of each colour
+    pub fn max(&self, other: &Draw) -> Draw {
+        let mut max = self.clone();
+        for (colour, count) in other.cubes.iter() {
+            let entry = max.cubes.entry(colour.clone()).or_default();
+            *entry = (*entry).max(*count);
         }
+        max
     }
+}
 
-    game_id
+impl<S: Into<String>> FromIterator<(S, u64)> for Draw {
+    // counts for a repeated colour are added together
+    fn from_iter<T: IntoIterator<Item = (S, u64)>>(iter: T) -> Self {
+        let mut draw = Draw::default();
+        for (colour, count) in iter {
+            *draw.cubes.entry(colour.into()).or_default() += count;
+        }
+        draw
+    }
 }
 
-fn day2_game_power(game: String) -> u64 {
-    let mut max_red = 0;
-    let mut max_green = 0;
-    let mut max_blue = 0;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Game {
+    id: u64,
+    draws: Vec<Draw>,
+}
 
-    let mut split = game.split([' ', ':']);
-    // skip the text "Game"
-    split.next();
-    // skp the game id
-    split.next().unwrap();
-    // skip empty token
-    split.next();
+impl Game {
+    pub fn is_possible(&self, bag: &Draw) -> bool {
+        self.draws.iter().all(|draw| bag.contains(draw))
+    }
 
-    while let Some(num) = split.next() {
-        let num: u64 = num.parse().unwrap();
-        let color = split.next().unwrap();
+    // the most cubes of each colour seen in a single draw
+    pub fn maximums(&self) -> Draw {
+        self.draws
+            .iter()
+            .fold(Draw::default(), |max, draw| max.max(draw))
+    }
 
-        match color.chars().next().unwrap() {
-            'r' => {
-                if num > max_red {
-                    max_red = num;
-                }
-            }
-            'g' => {
-                if num > max_green {
-                    max_green = num;
-                }
-            }
-            'b' => {
-                if num > max_blue {
-                    max_blue = num;
-                }
-            }
-            _ => {
-                panic!("unexpected input, expected a color name")
-            }
-        }
+    // the smallest bag the game could have been played with
+    pub fn minimum_bag(&self) -> Draw {
+        self.maximums()
     }
 
-    max_red * max_green * max_blue
+    // product of the minimum bag, over the colours seen in the game
+    pub fn power(&self) -> u64 {
+        self.minimum_bag().cubes.values().product()
+    }
 }
 
 fn day1() {
     let reader = BufReader::new(File::open("./input/2023/day1").unwrap());
-    let result = day1_logic(reader);
-    println!("{}", result);
-}
-
-// The current solution has lots of cloneing and could be seriously optimized
-// by using a state machine and finding everything using a single iteration through
-// each line and a state machine to keep track of the text based characters
-// but this is just for AdventOfCode, so I'm not gonne put in the work to optimize
-// it
-fn day1_logic(reader: impl BufRead) -> u64 {
-    reader
-        .lines()
-        .map(|x| x.unwrap())
-        .map(|x| insert_digits_from_text(x))
-        .map(|x| recover_calibration_value(x))
-        .sum()
+    let input = parse::read_input(reader).unwrap();
+
+    println!("Part 1: {}", day1_logic(&input, &digit_words(&[])).unwrap());
+    println!(
+        "Part 2: {}",
+        day1_logic(&input, &digit_words(&ENGLISH_DIGITS)).unwrap()
+    );
 }
 
-fn insert_digits_from_text(input: String) -> String {
-    let digits = [
-        ('0', "zero"),
-        ('1', "one"),
-        ('2', "two"),
-        ('3', "three"),
-        ('4', "four"),
-        ('5', "five"),
-        ('6', "six"),
-        ('7', "seven"),
-        ('8', "eight"),
-        ('9', "nine"),
-    ];
+const ENGLISH_DIGITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
 
-    let mut working = input;
-    for digit in digits {
-        let clone = working.clone();
-        let spelled_digits: Vec<_> = clone.match_indices(digit.1).collect();
-        for (extra, (i, _)) in spelled_digits.iter().enumerate() {
-            working.insert(i + 1 + extra, digit.0);
-        }
+// the digits 0 to 9, plus a spelled word for each value from 0
+fn digit_words(words: &[&str]) -> Trie<u64> {
+    let mut trie = Trie::new();
+
+    for digit in 0..10 {
+        trie.insert(&digit.to_string(), digit);
+    }
+    for (value, word) in words.iter().enumerate() {
+        trie.insert(word, value as u64);
     }
-    working
-}
 
-fn recover_calibration_value(line: String) -> u64 {
-    let mut digits = [None, None];
+    trie
+}
 
-    for c in line.chars() {
-        // ignore all non digit characters
-        if !c.is_ascii_digit() {
-            continue;
-        }
+// scans each line in place, the input is only read once for both parts
+fn day1_logic(input: &str, vocabulary: &Trie<u64>) -> Result<u64, ParseError> {
+    parse::lines(input)
+        .map(|line| {
+            recover_calibration_value(line.text, vocabulary)
+                .ok_or_else(|| line.error("line has no digits"))
+        })
+        .sum()
+}
 
-        if let None = digits[0] {
-            digits[0] = Some(c);
-            digits[1] = Some(c);
-        } else {
-            digits[1] = Some(c);
-        }
-    }
+// combines the first and last digit in the line, spelled words overlap
+// freely since they're found from each end independently
+fn recover_calibration_value(line: &str, vocabulary: &Trie<u64>) -> Option<u64> {
+    let (_, first) = vocabulary.first_match(line)?;
+    let (_, last) = vocabulary.last_match(line)?;
 
-    let result: String = digits.into_iter().map(|x| x.unwrap()).collect();
-    let result = result.parse::<u64>().unwrap();
-    result
+    Some(first * 10 + last)
 }
 
 #[cfg(test)]
@@ -1693,7 +1705,8 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(2286, day2p2_logic(input.as_bytes()));
+        let games = day2_parse(input.as_bytes()).unwrap();
+        assert_eq!(2286, day2p2_logic(&games));
     }
 
     #[test]
@@ -1705,7 +1718,41 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(8, _day2p1_logic(input.as_bytes()));
+        let games = day2_parse(input.as_bytes()).unwrap();
+        assert_eq!(8, day2p1_logic(&games));
+    }
+
+    #[test]
+    fn day2_games() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 12: 2 purple, 1 red, 1 purple; 7 gold";
+
+        let games = day2_parse(input.as_bytes()).unwrap();
+        assert_eq!(12, games[1].id);
+
+        let maximums = games[0].maximums();
+        assert_eq!(
+            (4, 2, 6),
+            (
+                maximums.get("red"),
+                maximums.get("green"),
+                maximums.get("blue")
+            )
+        );
+        assert_eq!(48, games[0].power());
+
+        // colours aren't fixed, and repeats within a draw add up
+        let bag = games[1].minimum_bag();
+        assert_eq!(
+            Draw::from_iter([("purple", 3), ("red", 1), ("gold", 7)]),
+            bag
+        );
+        assert_eq!(21, games[1].power());
+        assert!(games[1].is_possible(&bag));
+        assert!(!games[0].is_possible(&bag));
+
+        let error = day2_parse("Game 3: 1 blue, red".as_bytes()).err().unwrap();
+        assert_eq!(ParseError::new(1, 17, "expected `<count> <colour>`"), error);
     }
 
     #[test]
@@ -1719,7 +1766,41 @@ xtwone3four
 zoneight234
 7pqrstsixteen";
 
-        assert_eq!(281, day1_logic(input.as_bytes()));
+        assert_eq!(Ok(281), day1_logic(input, &digit_words(&ENGLISH_DIGITS)));
+    }
+
+    #[test]
+    fn day1_vocabularies() {
+        let english = digit_words(&ENGLISH_DIGITS);
+        assert_eq!(Some(82), recover_calibration_value("eightwo", &english));
+        assert_eq!(Some(77), recover_calibration_value("xseven", &english));
+        assert_eq!(None, recover_calibration_value("abc", &english));
+
+        // only numerals
+        let numerals = digit_words(&[]);
+        assert_eq!(Some(22), recover_calibration_value("eight2wo", &numerals));
+        assert_eq!(
+            Err(ParseError::new(2, 1, "line has no digits")),
+            day1_logic("a1b\neight", &numerals)
+        );
+
+        let german = digit_words(&[
+            "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ]);
+        assert_eq!(
+            Some(35),
+            recover_calibration_value("xdreiachtfünfy", &german)
+        );
+
+        // ordinals are words like any other
+        let ordinals = digit_words(&[
+            "zeroth", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth",
+            "ninth",
+        ]);
+        assert_eq!(
+            Some(19),
+            recover_calibration_value("firstninthree", &ordinals)
+        );
     }
 
     #[test]
@@ -1736,7 +1817,8 @@ zoneight234
 ...$.*....
 .664.598..";
 
-        assert_eq!(4361, _day3p1_logic(input.as_bytes()));
+        let schematic = day3_parse(input.as_bytes()).unwrap();
+        assert_eq!(4361, day3p1_logic(&schematic));
     }
 
     #[test]
@@ -1753,7 +1835,56 @@ zoneight234
 ...$.*....
 .664.598..";
 
-        assert_eq!(467835, day3p2_logic(input.as_bytes()));
+        let schematic = day3_parse(input.as_bytes()).unwrap();
+        assert_eq!(467835, day3p2_logic(&schematic));
+    }
+
+    #[test]
+    fn day3_schematic() {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+        let schematic = day3_parse(input.as_bytes()).unwrap();
+        assert_eq!(10, schematic.numbers().len());
+        assert_eq!(4361, day3p1_logic(&schematic));
+        assert_eq!(
+            vec![114, 58],
+            schematic
+                .numbers()
+                .iter()
+                .filter(|x| !x.is_part())
+                .map(|x| x.value)
+                .collect::<Vec<_>>()
+        );
+
+        let number = &schematic.numbers()[2];
+        assert_eq!(
+            (35, 2, Interval::new(2, 4)),
+            (number.value, number.row, number.columns)
+        );
+        assert_eq!(vec![(1, 3)], number.symbols);
+
+        let gears = schematic.gears();
+        assert_eq!(
+            vec![(1, 3), (8, 5)],
+            gears.iter().map(|x| x.position).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![467, 35], gears[0].parts);
+        assert_eq!(467835, gears.iter().map(Gear::ratio).sum::<u64>());
+
+        // the other `*` only touches one number
+        let lonely = schematic.symbols_with_neighbours('*', 1);
+        assert_eq!((4, 3), lonely[0].position);
+        assert_eq!(vec![617], lonely[0].parts);
+        assert_eq!(1, schematic.symbols_with_neighbours('$', 1).len());
     }
 
     #[test]
@@ -1765,7 +1896,7 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        let cards = day4_parser(input.as_bytes());
+        let cards = day4_parser(input.as_bytes()).unwrap();
 
         assert_eq!(13, day4p1_logic(&cards));
     }
@@ -1779,9 +1910,41 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        let cards = day4_parser(input.as_bytes());
+        let cards = day4_parser(input.as_bytes()).unwrap();
+
+        assert_eq!(30, day4p2_logic(&cards));
+    }
+
+    #[test]
+    fn day4_scoring_and_breakdown() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let cards = day4_parser(input.as_bytes()).unwrap();
+
+        assert_eq!(13, cards.points(Scoring::Doubling));
+        assert_eq!(4 + 2 + 2 + 1, cards.points(Scoring::Linear));
+        assert_eq!(3, cards.points(Scoring::Custom(|wins| (wins > 1) as u64)));
+        assert_eq!(vec![1, 2, 4, 8, 14, 1], cards.copies());
+
+        // doubling saturates instead of overflowing
+        assert_eq!(1 << 63, Scoring::Doubling.score(64));
+        assert_eq!(u64::MAX, Scoring::Doubling.score(65));
+
+        let breakdown = cards.breakdown();
+        assert_eq!(vec![(1, 1), (3, 4), (4, 8)], breakdown[4].sources);
+        assert_eq!(
+            "Card 4: 8 copies (1 original, 1 from card 1, 2 from card 2, 4 from card 3)",
+            breakdown[3].to_string()
+        );
+        assert_eq!("Card 6: 1 copies (1 original)", breakdown[5].to_string());
 
-        assert_eq!(30, day4p2_logic(cards));
+        // the last card's wins fall off the end of the pile
+        let cards = day4_parser("Card 1: 1 2 | 1 2".as_bytes()).unwrap();
+        assert_eq!(vec![1], cards.copies());
     }
 
     #[test]
@@ -1820,7 +1983,7 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let mut almanac: Almanac = day5_parse(input.as_bytes());
+        let almanac: Almanac = day5_parse(input.as_bytes()).unwrap();
         let mut locations = almanac.find_locations_p1();
         locations.sort_unstable();
 
@@ -1863,12 +2026,95 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let mut almanac: Almanac = day5_parse(input.as_bytes());
-        let mut location_ranges = almanac.find_locations();
+        let almanac: Almanac = day5_parse(input.as_bytes()).unwrap();
+        let location_ranges = almanac.find_locations();
         assert!(!location_ranges.is_empty());
 
-        location_ranges.sort_unstable_by(|span1, span2| span1.start.cmp(&span2.start));
-        assert_eq!(46, location_ranges.first().unwrap().start);
+        assert_eq!(Some(46), location_ranges.min());
+    }
+
+    #[test]
+    fn day5_composed_mapping() {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        let almanac: Almanac = day5_parse(input.as_bytes()).unwrap();
+        let mapping = almanac.seed_to_location();
+
+        for seed in 0..200 {
+            let location = almanac
+                .mappings
+                .iter()
+                .fold(seed, |value, mapping| mapping.map(value));
+            assert_eq!(location, mapping.map(seed));
+            assert!(mapping.preimage(location).contains(&seed));
+        }
+
+        assert_eq!(vec![82], almanac.seeds_for_location(46));
+
+        let error = day5_parse("seeds: 79 14 55\n\na-to-b map:\n1 2 3".as_bytes()).err();
+        assert_eq!(
+            Some(ParseError::new(
+                1,
+                14,
+                "seeds have to come in pairs of start and length"
+            )),
+            error
+        );
+
+        // mapping whole ranges agrees with mapping every value on its own
+        let seeds: RangeSet<u64> = [Interval::new(0, 200)].into_iter().collect();
+        let expected: RangeSet<u64> = (0..200)
+            .map(|seed| Interval::with_len(mapping.map(seed), 1))
+            .collect();
+        assert_eq!(expected, mapping.map_range(&seeds));
+
+        // every example mapping is one to one, so the chain can be inverted
+        let inverse = mapping.inverse().unwrap();
+        assert_eq!(82, inverse.map(46));
+
+        // empty ranges are allowed but don't map anything
+        let input = "seeds: 1 2\n\na-to-b map:\n50 98 0\n52 50 48\n\nb-to-c map:\n0 10 100";
+        let almanac: Almanac = day5_parse(input.as_bytes()).unwrap();
+        let mapping = almanac.seed_to_location();
+        for seed in [1, 60, 98, 99] {
+            let location = almanac
+                .mappings
+                .iter()
+                .fold(seed, |value, mapping| mapping.map(value));
+            assert_eq!(location, mapping.map(seed));
+        }
+        assert_eq!(52, mapping.map(60));
     }
 
     #[test]
@@ -1877,7 +2123,7 @@ humidity-to-location map:
         let input = "Time:      7  15   30
 Distance:  9  40  200";
 
-        let races = day6_parse(input.as_bytes());
+        let races = day6_parse(input.as_bytes()).unwrap();
         let p1: u64 = races
             .iter()
             .map(|race| {
@@ -1892,12 +2138,13 @@ Distance:  9  40  200";
     #[test]
     #[ignore]
     fn day7_hand_ordering() {
-        let hand1 = Hand::try_from("QQQJA").unwrap();
-        let hand2 = Hand::try_from("KTJJT").unwrap();
+        let rules = CamelRules::jokers();
+        let hand1 = rules.hand("QQQJA").unwrap();
+        let hand2 = rules.hand("KTJJT").unwrap();
 
         println!(
-            "QQQJA strength {}; KTJJT strength {}",
-            hand1.strength, hand2.strength
+            "QQQJA type {:?}; KTJJT type {:?}",
+            hand1.hand_type, hand2.hand_type
         );
         assert!(hand1 < hand2);
     }
@@ -1911,9 +2158,40 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-        let mut bids = day7_parse(input.as_bytes());
-        bids.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        assert_eq!(5905, day7p2_logic(&bids))
+        let mut bids = day7_parse(input.as_bytes(), &CamelRules::jokers()).unwrap();
+        assert_eq!(5905, rank_and_score(&mut bids))
+    }
+
+    #[test]
+    fn day7_rule_sets() {
+        let input = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+        let mut bids = day7_parse(input.as_bytes(), &CamelRules::standard()).unwrap();
+        assert_eq!(6440, rank_and_score(&mut bids));
+
+        let standard = CamelRules::standard();
+        let jokers = CamelRules::jokers();
+        assert_eq!(HandType::TwoPair, standard.hand("KTJJT").unwrap().hand_type);
+        assert_eq!(
+            HandType::FourOfAKind,
+            jokers.hand("KTJJT").unwrap().hand_type
+        );
+        assert_eq!(
+            HandType::FiveOfAKind,
+            jokers.hand("JJJJJ").unwrap().hand_type
+        );
+        // a lone joker is the weakest card but still completes the pair
+        assert!(jokers.hand("J2345").unwrap() < jokers.hand("22345").unwrap());
+        assert!(standard.hand("J2345").unwrap() < standard.hand("22345").unwrap());
+        assert_eq!(Err("Hands must be 5 cards"), standard.hand("AAAA"));
+
+        // any card can be wild, sevens here
+        let sevens = CamelRules::new("23456789TJQKA", "7");
+        assert_eq!(HandType::FullHouse, sevens.hand("KK7QQ").unwrap().hand_type);
     }
 
     #[test]
@@ -1929,8 +2207,24 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
 
-        let (instructions, mut adjacency) = day8_parse(input.as_bytes());
-        assert_eq!(2, day8_p1(&instructions, &mut adjacency));
+        let (instructions, mut adjacency) = day8_parse(input.as_bytes()).unwrap();
+        assert_eq!(Some(2), day8_p1(&instructions, &mut adjacency));
+    }
+
+    #[test]
+    fn day8_malformed_network() {
+        let error = day8_parse("LR\n\nAAA = (BB, ZZZ)".as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(
+            ParseError::new(3, 8, "expected a three character node name"),
+            error
+        );
+
+        // a node leading nowhere stops the walk instead of panicking
+        let (instructions, mut adjacency) = day8_parse("R\n\nAAA = (AAA, BBB)".as_bytes()).unwrap();
+        assert_eq!(None, day8_p1(&instructions, &mut adjacency));
+        assert_eq!(None, day8_p2(&instructions, &mut adjacency));
     }
 
     #[test]
@@ -1940,11 +2234,14 @@ ZZZ = (ZZZ, ZZZ)";
 1 3 6 10 15 21
 10 13 16 21 30 45";
 
-        let history = day9_parse(input.as_bytes());
-        let (p2_result, p1_result) = history.iter().fold((0i64, 0i64), |(back, forward), diffs| {
-            let (b, f) = day9_predict_values(diffs);
-            (back + b, forward + f)
-        });
+        let history = day9_parse(input.as_bytes()).unwrap();
+        let (p2_result, p1_result) =
+            history
+                .iter()
+                .fold((0i128, 0i128), |(back, forward), values| {
+                    let (b, f) = day9_predict_values(values).unwrap();
+                    (back + b, forward + f)
+                });
         assert_eq!(114, p1_result);
         assert_eq!(2, p2_result);
     }
@@ -1958,7 +2255,7 @@ ZZZ = (ZZZ, ZZZ)";
 .L-J.
 .....";
 
-        let mut land = day10_parse(input.as_bytes());
+        let land = day10_parse(input.as_bytes()).unwrap();
         assert_eq!(4, land.find_furthest_length());
     }
 
@@ -1971,7 +2268,7 @@ SJ.L7
 |F--J
 LJ...";
 
-        let mut land = day10_parse(input.as_bytes());
+        let land = day10_parse(input.as_bytes()).unwrap();
         assert_eq!(8, land.find_furthest_length());
     }
 
@@ -1988,8 +2285,7 @@ LJ...";
 .L--J.L--J.
 ...........";
 
-        let mut land = day10_parse(input.as_bytes());
-        land.find_furthest_length();
+        let land = day10_parse(input.as_bytes()).unwrap();
         assert_eq!(4, land.count_enclosed_ground());
     }
 
@@ -2007,8 +2303,7 @@ L--J.L7...LJS7F-7L7.
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...";
 
-        let mut land = day10_parse(input.as_bytes());
-        land.find_furthest_length();
+        let land = day10_parse(input.as_bytes()).unwrap();
         assert_eq!(8, land.count_enclosed_ground());
     }
 
@@ -2026,8 +2321,120 @@ L---JF-JLJ.||-FJLJJ7
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L";
 
-        let mut land = day10_parse(input.as_bytes());
-        land.find_furthest_length();
+        let land = day10_parse(input.as_bytes()).unwrap();
         assert_eq!(10, land.count_enclosed_ground());
     }
+
+    #[test]
+    fn day10_start_and_loop() {
+        // the start has three pipes pointing at it, only two form the loop
+        let input = "...|.
+.F-S7
+.|.FJ
+.L-J.";
+
+        let land = day10_parse(input.as_bytes()).unwrap();
+        assert_eq!('-', land.grid[1][3].c);
+        assert_eq!(
+            vec![(1, 3), (1, 4), (2, 4), (2, 3)],
+            land.main_loop()[..4].to_vec()
+        );
+        assert_eq!(10, land.main_loop().len());
+        assert_eq!(5, land.find_furthest_length());
+
+        let rendered = land.render();
+        assert!(rendered.contains("\x1b[1;33m┐"));
+        assert!(rendered.contains("\x1b[2;34m│"));
+        assert!(rendered.contains("\x1b[32m·"));
+        assert_eq!(1, land.count_enclosed_ground());
+        assert_eq!(Location::Inside, land.locations()[2][2]);
+
+        // the loop has to come back through the start's other pipe
+        let input = ".....
+.FS7.
+.|||.
+.L-J.
+.....";
+        let land = day10_parse(input.as_bytes()).unwrap();
+        assert_eq!('-', land.grid[1][2].c);
+        assert_eq!(8, land.main_loop().len());
+
+        let error = day10_parse("...\n.S-\n...".as_bytes()).err().unwrap();
+        assert_eq!(
+            ParseError::new(2, 2, "start tile isn't part of a loop"),
+            error
+        );
+    }
+
+    #[test]
+    fn day11_pair_distances() {
+        let input = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+        let map = day11_parse(input.as_bytes()).unwrap();
+        let distances = map.pair_distances();
+        assert_eq!(374, distances.at(2));
+        assert_eq!(1030, distances.at(10));
+        assert_eq!(8410, distances.at(100));
+
+        // distances in the expanded universe need no further inflation
+        let expanded = map.expand(10);
+        assert_eq!(1030, expanded.pair_distances().at(1));
+        assert_eq!(20, expanded.empty_rows.total_len());
+    }
+
+    #[test]
+    fn day11_sparse_expansion() {
+        let input = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+        let expected = "....#........
+.........#...
+#............
+.............
+.............
+........#....
+.#...........
+............#
+.............
+.............
+.........#...
+#....#.......
+";
+
+        let map = day11_parse(input.as_bytes()).unwrap();
+        assert_eq!(input, map.to_string());
+        assert_eq!(expected, map.expand(2).to_string());
+        assert_eq!(map.expand(2), day11_parse(expected.as_bytes()).unwrap());
+
+        // far too large to ever draw
+        let huge = map.expand(1_000_000_000_000);
+        assert_eq!((2_000_000_000_007, 0), huge.galaxies[7]);
+        assert_eq!(huge.galaxies, map.expanded_galaxies(1_000_000_000_000));
+        assert_eq!(
+            map.pair_distances().at(1_000_000_000_000),
+            huge.pair_distances().at(1)
+        );
+
+        // a factor of 0 squeezes the empty space out
+        let collapsed = map.expand(0);
+        assert!(collapsed.empty_rows.is_empty() && collapsed.empty_cols.is_empty());
+        assert_eq!(map.pair_distances().at(0), collapsed.pair_distances().at(1));
+    }
 }
@@ -1,15 +1,19 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     fs::File,
     io::{BufRead, BufReader, Read},
-    iter::repeat_n,
 };
 
-use itertools::Itertools;
-use nalgebra::{Const, DMatrix, Dyn, Matrix, VecStorage, ViewStorage};
-use regex::Regex;
-
-use crate::{AdventYear, Year};
+use crate::{
+    utils::{
+        bitset::BitSet,
+        grid::Grid,
+        parse::{self, ParseError},
+        partial_order::{Cycle, PrecedenceGraph},
+        word_search::{Pattern, WordSearch},
+    },
+    AdventYear, Year,
+};
 
 pub fn init() -> Box<dyn AdventYear> {
     let days: Vec<Box<dyn Fn()>> = vec![
@@ -26,427 +30,343 @@ pub fn init() -> Box<dyn AdventYear> {
 
 fn day6() {
     let reader = BufReader::new(File::open("./input/2024/day6").unwrap());
-    let (map, guard) = day6_parse(reader);
-    let mut p1map = map.clone();
-    let mut p1guard = guard.clone();
-    let (count, _) = day6p1(&mut p1map, &mut p1guard);
-    let loops = day6p2(map, guard);
-    println!("\nPart 1: {}", count);
-    println!("Part 2: {}", loops);
-}
-
-fn day6p2(map: Vec<Vec<D6State>>, guard: GuardLocation) -> usize {
-    let mut loop_count: usize = 0;
-    for r in 0..map.len() {
-        println!("\nChecking row: {}", r);
-
-        for c in 0..(map[0].len()) {
-            print!(", {}", c);
-            let mut cmap = map.clone();
-            let mut cguard = guard.clone();
-            cmap[r][c] = D6State::Obstacle;
-
-            let (_count, loops) = day6p1(&mut cmap, &mut cguard);
-            if loops {
-                loop_count += 1;
+    let patrol = day6_parse(reader).unwrap();
+
+    println!("Part 1: {}", day6p1_logic(&patrol));
+    println!("Part 2: {}", day6p2_logic(&patrol));
+}
+
+// counts the cells the guard visits before leaving the map
+fn day6p1_logic(patrol: &Patrol) -> usize {
+    patrol.visited_cells().count()
+}
+
+// counts the cells where a new obstacle traps the guard in a loop
+fn day6p2_logic(patrol: &Patrol) -> usize {
+    patrol.loop_positions().len()
+}
+
+fn day6_parse(reader: impl BufRead) -> Result<Patrol, ParseError> {
+    let input = parse::read_input(reader)?;
+    let grid = Grid::parse(&input, |c| match c {
+        '.' => Ok(Tile::Open),
+        '#' => Ok(Tile::Obstacle),
+        '^' => Ok(Tile::Guard(Heading::Up)),
+        '>' => Ok(Tile::Guard(Heading::Right)),
+        'v' => Ok(Tile::Guard(Heading::Down)),
+        '<' => Ok(Tile::Guard(Heading::Left)),
+        x => Err(format!("unexpected character `{}`", x)),
+    })?;
+
+    let mut start = None;
+    for row in 0..grid.rows() {
+        for (col, tile) in grid.row(row).iter().enumerate() {
+            let Tile::Guard(heading) = tile else {
+                continue;
+            };
+            if start.is_some() {
+                return Err(ParseError::new(row + 1, col + 1, "found a second guard"));
             }
+            start = Some(GuardLocation::new(row, col, *heading));
         }
     }
 
-    loop_count
+    let start = start.ok_or_else(|| ParseError::new(1, 1, "no guard found"))?;
+    Ok(Patrol::new(grid, start))
 }
 
-fn day6p1(map: &mut Vec<Vec<D6State>>, guard: &mut GuardLocation) -> (usize, bool) {
-    let mut position_count: usize = 1;
-    let mut visited: HashSet<GuardLocation> = HashSet::new();
-    loop {
-        if visited.contains(guard) {
-            return (position_count, true);
-        }
-        visited.insert(guard.clone());
+/// The way the guard is facing, clockwise from up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Heading {
+    Up,
+    Right,
+    Down,
+    Left,
+}
 
-        match guard.orientation {
-            D6State::GuardUp => {
-                if guard.row == 0 {
-                    break;
-                }
-                if map[guard.row - 1][guard.col] == D6State::Obstacle {
-                    guard.orientation = D6State::GuardRight;
-                } else if map[guard.row - 1][guard.col] == D6State::Unvisitied {
-                    guard.row -= 1;
-                    map[guard.row][guard.col] = D6State::GuardUp;
-                    position_count += 1;
-                } else {
-                    guard.row -= 1;
-                }
-            }
-            D6State::GuardDown => {
-                if guard.row == map.len() - 1 {
-                    break;
-                }
-                if map[guard.row + 1][guard.col] == D6State::Obstacle {
-                    guard.orientation = D6State::GuardLeft;
-                } else if map[guard.row + 1][guard.col] == D6State::Unvisitied {
-                    guard.row += 1;
-                    map[guard.row][guard.col] = D6State::GuardDown;
-                    position_count += 1;
-                } else {
-                    guard.row += 1;
-                }
-            }
-            D6State::GuardLeft => {
-                if guard.col == 0 {
-                    break;
-                }
-                if map[guard.row][guard.col - 1] == D6State::Obstacle {
-                    guard.orientation = D6State::GuardUp;
-                } else if map[guard.row][guard.col - 1] == D6State::Unvisitied {
-                    guard.col -= 1;
-                    map[guard.row][guard.col] = D6State::GuardLeft;
-                    position_count += 1;
-                } else {
-                    guard.col -= 1;
-                }
-            }
-            D6State::GuardRight => {
-                if guard.col == map[0].len() - 1 {
-                    break;
-                }
-                if map[guard.row][guard.col + 1] == D6State::Obstacle {
-                    guard.orientation = D6State::GuardDown;
-                } else if map[guard.row][guard.col + 1] == D6State::Unvisitied {
-                    guard.col += 1;
-                    map[guard.row][guard.col] = D6State::GuardRight;
-                    position_count += 1;
-                } else {
-                    guard.col += 1;
-                }
-            }
-            _ => panic!("Guard not in position"),
+impl Heading {
+    const ALL: [Heading; 4] = [Heading::Up, Heading::Right, Heading::Down, Heading::Left];
+
+    pub fn turn_right(&self) -> Heading {
+        Heading::ALL[(*self as usize + 1) % 4]
+    }
+
+    // row and column step
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Heading::Up => (-1, 0),
+            Heading::Right => (0, 1),
+            Heading::Down => (1, 0),
+            Heading::Left => (0, -1),
         }
     }
+}
 
-    (position_count, false)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Open,
+    Obstacle,
+    Guard(Heading),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct GuardLocation {
     row: usize,
     col: usize,
-    orientation: D6State,
+    heading: Heading,
 }
 
 impl GuardLocation {
-    fn new(row: usize, col: usize, orientation: D6State) -> GuardLocation {
-        GuardLocation {
-            row,
-            col,
-            orientation,
-        }
+    fn new(row: usize, col: usize, heading: Heading) -> GuardLocation {
+        GuardLocation { row, col, heading }
     }
 }
 
-fn day6_parse(reader: impl BufRead) -> (Vec<Vec<D6State>>, GuardLocation) {
-    let mut start = GuardLocation::new(0, 0, D6State::Unvisitied);
-    (
-        reader
-            .lines()
-            .enumerate()
-            .map(|(r, x)| {
-                x.unwrap()
-                    .chars()
-                    .enumerate()
-                    .map(|(col, c)| match c {
-                        '.' => D6State::Unvisitied,
-                        '#' => D6State::Obstacle,
-                        '^' => {
-                            assert!(start.orientation == D6State::Unvisitied);
-                            start.orientation = D6State::GuardUp;
-                            start.row = r;
-                            start.col = col;
-                            D6State::GuardUp
-                        }
-                        'v' => {
-                            assert!(start.orientation == D6State::Unvisitied);
-                            start.orientation = D6State::GuardDown;
-                            start.row = r;
-                            start.col = col;
-                            D6State::GuardDown
-                        }
-                        '<' => {
-                            assert!(start.orientation == D6State::Unvisitied);
-                            start.orientation = D6State::GuardLeft;
-                            start.row = r;
-                            start.col = col;
-                            D6State::GuardLeft
-                        }
-                        '>' => {
-                            assert!(start.orientation == D6State::Unvisitied);
-                            start.orientation = D6State::GuardRight;
-                            start.row = r;
-                            start.col = col;
-                            D6State::GuardRight
-                        }
-                        _ => panic!("unexpected input"),
-                    })
-                    .collect_vec()
-            })
-            .collect_vec(),
-        start,
-    )
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-enum D6State {
-    Obstacle,
-    Unvisitied,
-    GuardUp,
-    GuardDown,
-    GuardLeft,
-    GuardRight,
+/// A guard walking forwards and turning right at every obstacle
+struct Patrol {
+    grid: Grid<Tile>,
+    start: GuardLocation,
+    // for every cell and heading, the cell in front of the next obstacle, or
+    // none when the guard walks off the map
+    jumps: Vec<[Option<(usize, usize)>; 4]>,
 }
 
-fn day5() {
-    let reader = BufReader::new(File::open("./input/2024/day5").unwrap());
-    let (rules, mut updates) = day5_parse(reader);
-
-    println!("Part 1: {}", day5p1_logic(&rules, &mut updates));
-    day5p2_logic(&rules, &mut updates);
-    println!("Part 2: {}", day5p1_logic(&rules, &mut updates));
-}
-
-fn day5p2_logic(rules: &HashMap<i32, Vec<i32>>, updates: &mut Vec<Vec<i32>>) {
-    let mut i = 0;
-    while i < updates.len() {
-        let mut seen: HashMap<i32, usize> = HashMap::new();
-        let mut broke_rule = false;
-        let mut remove_index: usize = 0;
-        let mut insert_index: usize = 0;
-
-        let update = updates.get_mut(i).unwrap();
-        for j in 0..update.len() {
-            let page = update[j];
-            if let Some(rule) = rules.get(&page) {
-                for p in rule {
-                    if seen.contains_key(p) {
-                        broke_rule = true;
-                        insert_index = *seen.get(p).unwrap();
-                        remove_index = j;
-                        assert!(remove_index > insert_index);
-                        break;
-                    }
-                }
+impl Patrol {
+    pub fn new(grid: Grid<Tile>, start: GuardLocation) -> Patrol {
+        let mut jumps = vec![[None; 4]; grid.rows() * grid.cols()];
+        let cells: Vec<_> = (0..grid.rows())
+            .flat_map(|row| (0..grid.cols()).map(move |col| (row, col)))
+            .collect();
+
+        // the cell ahead is always filled in first, going up or left it comes
+        // earlier in reading order and going down or right it comes later
+        for heading in Heading::ALL {
+            let order: Box<dyn Iterator<Item = &(usize, usize)>> = match heading {
+                Heading::Up | Heading::Left => Box::new(cells.iter()),
+                Heading::Down | Heading::Right => Box::new(cells.iter().rev()),
+            };
+
+            for (row, col) in order {
+                let jump = match ahead(&grid, (*row, *col), heading) {
+                    None => None,
+                    Some((r, c)) if grid.get(r, c) == Some(&Tile::Obstacle) => Some((*row, *col)),
+                    Some((r, c)) => jumps[r * grid.cols() + c][heading as usize],
+                };
+                jumps[row * grid.cols() + col][heading as usize] = jump;
             }
+        }
 
-            if broke_rule {
+        Patrol { grid, start, jumps }
+    }
+
+    fn index(&self, (row, col): (usize, usize)) -> usize {
+        row * self.grid.cols() + col
+    }
+
+    fn is_obstacle(&self, (row, col): (usize, usize)) -> bool {
+        self.grid.get(row, col) == Some(&Tile::Obstacle)
+    }
+
+    /// Every step of the guard's route, ending as they leave the map
+    ///
+    /// turning is a step of its own, and the route stops early if the guard
+    /// would be stuck in a loop
+    pub fn route(&self) -> Vec<GuardLocation> {
+        let mut seen = BitSet::new(self.jumps.len() * 4);
+        let mut route = Vec::new();
+        let mut guard = self.start;
+
+        while seen.insert(self.index((guard.row, guard.col)) * 4 + guard.heading as usize) {
+            route.push(guard);
+
+            let Some((row, col)) = ahead(&self.grid, (guard.row, guard.col), guard.heading) else {
                 break;
+            };
+            if self.is_obstacle((row, col)) {
+                guard.heading = guard.heading.turn_right();
+            } else {
+                (guard.row, guard.col) = (row, col);
             }
-
-            seen.insert(page, j);
-        }
-        if broke_rule {
-            let remove = updates[i].remove(remove_index);
-            updates[i].insert(insert_index, remove);
-        } else {
-            i += 1;
         }
+
+        route
     }
-}
 
-fn day5p1_logic(rules: &HashMap<i32, Vec<i32>>, updates: &mut Vec<Vec<i32>>) -> i32 {
-    let mut correct: Vec<usize> = Vec::new();
-    let mut middle_sum: i32 = 0;
-    for (index, update) in updates.iter().enumerate() {
-        let mut seen = HashSet::new();
-        let mut broke_rule = false;
-        for page in update {
-            if let Some(rule) = rules.get(page) {
-                for p in rule {
-                    if seen.contains(p) {
-                        broke_rule = true;
-                        break;
-                    }
-                }
+    // the distinct cells on the guard's route, in the order they're reached
+    pub fn visited_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut seen = BitSet::new(self.jumps.len());
+        self.route()
+            .into_iter()
+            .map(|guard| (guard.row, guard.col))
+            .filter(move |cell| seen.insert(self.index(*cell)))
+    }
+
+    /// Every cell where one new obstacle makes the guard walk in a loop
+    ///
+    /// only cells on the route can change it, and the guard behaves the same
+    /// until they first reach the new obstacle, so each cell is only tried
+    /// from the step before the guard first walks into it
+    pub fn loop_positions(&self) -> Vec<(usize, usize)> {
+        let mut tried = BitSet::new(self.jumps.len());
+        tried.insert(self.index((self.start.row, self.start.col)));
+
+        let mut positions = Vec::new();
+        for guard in self.route() {
+            let Some(cell) = ahead(&self.grid, (guard.row, guard.col), guard.heading) else {
+                continue;
+            };
+            if self.is_obstacle(cell) || !tried.insert(self.index(cell)) {
+                continue;
             }
 
-            if broke_rule {
-                break;
+            if self.loops_with(guard, cell) {
+                positions.push(cell);
             }
-            seen.insert(*page);
         }
-        if !broke_rule {
-            middle_sum += update[update.len() / 2];
-            correct.push(index);
+
+        positions
+    }
+
+    // follows the jump table from the guard's location with an extra obstacle,
+    // only remembering where the guard turns
+    fn loops_with(&self, guard: GuardLocation, obstacle: (usize, usize)) -> bool {
+        let mut turns = BitSet::new(self.jumps.len() * 4);
+        let (mut cell, mut heading) = ((guard.row, guard.col), guard.heading);
+
+        loop {
+            let jump = self.jumps[self.index(cell)][heading as usize];
+            let blocked = distance(cell, heading, obstacle).filter(|d| {
+                jump.is_none_or(|stop| *d <= distance(cell, heading, stop).unwrap_or(0))
+            });
+
+            cell = match (blocked, jump) {
+                (Some(d), _) => {
+                    let (dr, dc) = heading.offset();
+                    let steps = d as isize - 1;
+                    (
+                        cell.0.wrapping_add_signed(dr * steps),
+                        cell.1.wrapping_add_signed(dc * steps),
+                    )
+                }
+                (None, Some(stop)) => stop,
+                (None, None) => return false,
+            };
+
+            heading = heading.turn_right();
+            if !turns.insert(self.index(cell) * 4 + heading as usize) {
+                return true;
+            }
         }
     }
+}
 
-    for index in correct.into_iter().rev() {
-        updates.remove(index);
+// the next cell the way the guard is facing, if it's still on the map
+fn ahead(
+    grid: &Grid<Tile>,
+    (row, col): (usize, usize),
+    heading: Heading,
+) -> Option<(usize, usize)> {
+    let (dr, dc) = heading.offset();
+    let row = row.checked_add_signed(dr)?;
+    let col = col.checked_add_signed(dc)?;
+    grid.get(row, col).map(|_| (row, col))
+}
+
+// number of steps from one cell to another, if it's straight ahead
+fn distance(from: (usize, usize), heading: Heading, to: (usize, usize)) -> Option<usize> {
+    match heading {
+        Heading::Up => (from.1 == to.1 && to.0 < from.0).then(|| from.0 - to.0),
+        Heading::Down => (from.1 == to.1 && to.0 > from.0).then(|| to.0 - from.0),
+        Heading::Left => (from.0 == to.0 && to.1 < from.1).then(|| from.1 - to.1),
+        Heading::Right => (from.0 == to.0 && to.1 > from.1).then(|| to.1 - from.1),
     }
+}
+
+fn day5() {
+    let reader = BufReader::new(File::open("./input/2024/day5").unwrap());
+    let (rules, updates) = day5_parse(reader).unwrap();
 
-    middle_sum
+    println!("Part 1: {}", day5p1_logic(&rules, &updates));
+    println!("Part 2: {}", day5p2_logic(&rules, &updates).unwrap());
 }
 
-fn day5_parse(reader: impl BufRead) -> (HashMap<i32, Vec<i32>>, Vec<Vec<i32>>) {
-    let mut rules: HashMap<i32, Vec<i32>> = HashMap::new();
-    let mut updates: Vec<Vec<i32>> = Vec::new();
+// sorts the incorrectly ordered updates and sums their middle pages
+fn day5p2_logic(rules: &PrecedenceGraph<i32>, updates: &[Vec<i32>]) -> Result<i32, Cycle<i32>> {
+    updates
+        .iter()
+        .filter(|update| !rules.is_ordered(update))
+        .map(|update| {
+            let sorted = rules.sort(update)?;
+            Ok(sorted[sorted.len() / 2])
+        })
+        .sum()
+}
 
-    let mut read_rule = true;
-    for line in reader.lines().map(|x| x.unwrap()) {
-        if line.len() < 3 {
-            read_rule = false;
-            continue;
-        };
+// sums the middle pages of the correctly ordered updates
+fn day5p1_logic(rules: &PrecedenceGraph<i32>, updates: &[Vec<i32>]) -> i32 {
+    updates
+        .iter()
+        .filter(|update| rules.is_ordered(update))
+        .map(|update| update[update.len() / 2])
+        .sum()
+}
 
-        if read_rule {
-            let mut nums = line.split('|');
-            let left: i32 = nums.next().unwrap().parse().unwrap();
-            let right: i32 = nums.next().unwrap().parse().unwrap();
-            rules
-                .entry(left)
-                .and_modify(|x| x.push(right))
-                .or_insert(vec![right]);
-        } else {
-            let update: Vec<i32> = line.split(',').map(|x| x.parse().unwrap()).collect();
-            updates.push(update);
-        }
+fn day5_parse(reader: impl BufRead) -> Result<(PrecedenceGraph<i32>, Vec<Vec<i32>>), ParseError> {
+    let input = parse::read_input(reader)?;
+    let mut sections = parse::sections(&input).into_iter();
+    let mut rules: PrecedenceGraph<i32> = PrecedenceGraph::new();
+
+    for line in sections.next().unwrap_or_default() {
+        let (left, right) = line.split_once('|')?;
+        rules.add_rule(left.parse()?, right.parse()?);
     }
 
-    (rules, updates)
+    let updates = sections
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|line| {
+            line.split(',')
+                .map(|x| x.parse())
+                .collect::<Result<Vec<i32>, _>>()
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((rules, updates))
 }
 
 fn day4() {
     let reader = BufReader::new(File::open("./input/2024/day4").unwrap());
-    let mut data = day4_parse(reader);
-    println!("Part 1: {}", day4p1_logic(&mut data));
-    println!("Part 2: {}", day4p2_logic(&mut data));
-}
-
-fn day4p2_logic(data: &mut Matrix<i32, Dyn, Dyn, VecStorage<i32, Dyn, Dyn>>) -> usize {
-    let mut cycle: Vec<i32> = vec![0; data.ncols() - 1];
-    *cycle.last_mut().unwrap() = 1;
-    let mut reflect = DMatrix::from_row_iterator(
-        data.nrows(),
-        data.ncols(),
-        repeat_n(0i32, 1).chain(cycle.into_iter().cycle()),
-    );
-    *reflect
-        .get_mut((reflect.nrows() - 1, reflect.ncols() - 1))
-        .unwrap() = 0;
-    let mut counter: usize = 0;
-    for _ in 0..4 {
-        let (r, c) = data.shape();
-        for row in 0..(r - 2) {
-            for col in 0..(c - 2) {
-                if *data.get((row, col)).unwrap() == 2 {
-                    counter += day4_check_x_mas(data.view((row, col), (3, 3)));
-                }
-            }
-        }
+    let search = day4_parse(reader).unwrap();
+    println!("Part 1: {}", day4p1_logic(&search));
+    println!("Part 2: {}", day4p2_logic(&search));
+}
 
-        let new_data = (&reflect) * data.transpose();
-        *data = new_data;
-    }
-
-    counter
-}
-
-fn day4_check_x_mas(
-    view: Matrix<i32, Dyn, Dyn, ViewStorage<'_, i32, Dyn, Dyn, Const<1>, Dyn>>,
-) -> usize {
-    // check for x-mas
-    if *view.get((0, 0)).unwrap() == 2
-        && *view.get((0, 2)).unwrap() == 2
-        && *view.get((1, 1)).unwrap() == 3
-        && *view.get((2, 0)).unwrap() == 4
-        && *view.get((2, 2)).unwrap() == 4
-    {
-        1
-    } else {
-        0
-    }
-}
-
-fn day4p1_logic(data: &mut Matrix<i32, Dyn, Dyn, VecStorage<i32, Dyn, Dyn>>) -> usize {
-    let mut cycle: Vec<i32> = vec![0; data.ncols() - 1];
-    *cycle.last_mut().unwrap() = 1;
-    let mut reflect = DMatrix::from_row_iterator(
-        data.nrows(),
-        data.ncols(),
-        repeat_n(0i32, 1).chain(cycle.into_iter().cycle()),
-    );
-    *reflect
-        .get_mut((reflect.nrows() - 1, reflect.ncols() - 1))
-        .unwrap() = 0;
-    let mut counter: usize = 0;
-    for _ in 0..4 {
-        let (r, c) = data.shape();
-        for row in 0..(r) {
-            for col in 0..(c - 3) {
-                if *data.get((row, col)).unwrap() == 1 {
-                    let check_diag = row <= (r - 4);
-                    if check_diag {
-                        counter += day4_check_xmas(data.view((row, col), (4, 4)), check_diag);
-                    } else {
-                        counter += day4_check_xmas(data.view((row, col), (1, 4)), check_diag);
-                    }
-                }
-            }
-        }
+fn day4p2_logic(search: &WordSearch) -> usize {
+    // both diagonals read MAS in either direction
+    let cross = Pattern::new(&["M.S", ".A.", "M.S"], '.');
+    search.find_pattern(&cross, true).len()
+}
 
-        let new_data = (&reflect) * data.transpose();
-        *data = new_data;
-    }
-
-    counter
-}
-
-fn day4_check_xmas(
-    view: Matrix<i32, Dyn, Dyn, ViewStorage<'_, i32, Dyn, Dyn, Const<1>, Dyn>>,
-    check_diag: bool,
-) -> usize {
-    let mut counter = 0;
-    // check straight ahead
-    if *view.get((0, 0)).unwrap() == 1
-        && *view.get((0, 1)).unwrap() == 2
-        && *view.get((0, 2)).unwrap() == 3
-        && *view.get((0, 3)).unwrap() == 4
-    {
-        counter += 1;
-    }
-    // check diagonal
-    if check_diag
-        && *view.get((0, 0)).unwrap() == 1
-        && *view.get((1, 1)).unwrap() == 2
-        && *view.get((2, 2)).unwrap() == 3
-        && *view.get((3, 3)).unwrap() == 4
-    {
-        counter += 1;
-    }
-
-    counter
-}
-
-fn day4_parse(mut reader: impl BufRead) -> DMatrix<i32> {
-    let mut data: String = String::new();
-    reader
-        .read_to_string(&mut data)
-        .expect("Failed to read input data");
-    let row_length = data.lines().next().unwrap().len();
-    let col_length = data.lines().count();
-
-    DMatrix::from_row_iterator(
-        col_length,
-        row_length,
-        data.chars().filter_map(|c| match c {
-            'X' => Some(1),
-            'M' => Some(2),
-            'A' => Some(3),
-            'S' => Some(4),
-            '\n' => None,
-            _ => Some(0),
-        }),
-    )
+fn day4p1_logic(search: &WordSearch) -> usize {
+    search.find_word("XMAS", true).len()
+}
+
+fn day4_parse(reader: impl BufRead) -> Result<WordSearch, ParseError> {
+    let data = parse::read_input(reader)?;
+    let row_length = data.lines().next().map_or(0, |x| x.len());
+
+    // every row has to be the same length to form a grid
+    if let Some(line) = parse::lines(&data).find(|x| x.text.len() != row_length) {
+        return Err(line.error_at(
+            line.text.len().min(row_length),
+            "row length differs from the first row",
+        ));
+    }
+
+    Ok(WordSearch::new(
+        data.lines().map(|line| line.chars().collect()).collect(),
+    ))
 }
 
 fn day3() {
@@ -454,182 +374,307 @@ fn day3() {
     File::read_to_string(&mut File::open("./input/2024/day3").unwrap(), &mut input)
         .expect("Failed to read input to string");
 
-    let re = Regex::new(r"(mul\([0-9]{1,3},[0-9]{1,3}\))|(do\(\))|(don't\(\))").unwrap();
-
-    let mut p1_result = 0;
-    let mut p2_result = 0;
-    let mut enable = true;
-
-    for capture in re.captures_iter(&input) {
-        match &capture.get(0).unwrap().as_str()[..3] {
-            "do(" => enable = true,
-            "don" => enable = false,
-            "mul" => {
-                let slice = &capture.get(0).unwrap().as_str()[4..];
-                let slice = &slice[..(slice.len() - 1)];
-                let (left, right) = slice
-                    .split(',')
-                    .map(|x| x.parse::<i32>().unwrap())
-                    .next_tuple()
-                    .unwrap();
-                let mult = left * right;
-
-                p1_result += mult;
-                if enable {
-                    p2_result += mult;
-                }
-            }
-            _ => {
-                panic!("Unexpected capture group!")
+    println!("Part 1: {}", day3_scanner(false).run(&input).machine.total);
+    println!("Part 2: {}", day3_scanner(true).run(&input).machine.total);
+}
+
+// multiplications, optionally switched on and off by do() and don't()
+fn day3_scanner(conditionals: bool) -> MemoryScanner {
+    let mut scanner = MemoryScanner::new(3);
+    scanner.register("mul", 2, true, |machine, args| {
+        machine.total += args[0] * args[1]
+    });
+
+    if conditionals {
+        scanner.register("do", 0, false, |machine, _| machine.enabled = true);
+        scanner.register("don't", 0, false, |machine, _| machine.enabled = false);
+    }
+
+    scanner
+}
+
+/// State changed by the instructions of a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Machine {
+    enabled: bool,
+    total: i64,
+}
+
+type Handler = fn(&mut Machine, &[i64]);
+
+struct Instruction {
+    name: &'static str,
+    arity: usize,
+    // only runs while the machine is enabled
+    conditional: bool,
+    handler: Handler,
+}
+
+/// An instruction found in memory, in the order they were found
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    position: usize,
+    name: &'static str,
+    args: Vec<i64>,
+    // false when skipped because the machine was disabled
+    executed: bool,
+}
+
+struct Execution {
+    machine: Machine,
+    #[allow(dead_code)]
+    trace: Vec<Step>,
+}
+
+/// Finds and runs `name(arg,...)` instructions hidden in corrupted memory
+struct MemoryScanner {
+    instructions: Vec<Instruction>,
+    // longest number an argument can be written with
+    max_digits: usize,
+}
+
+impl MemoryScanner {
+    fn new(max_digits: usize) -> MemoryScanner {
+        MemoryScanner {
+            instructions: Vec::new(),
+            max_digits,
+        }
+    }
+
+    fn register(&mut self, name: &'static str, arity: usize, conditional: bool, handler: Handler) {
+        self.instructions.push(Instruction {
+            name,
+            arity,
+            conditional,
+            handler,
+        });
+    }
+
+    fn run(&self, memory: &str) -> Execution {
+        let mut machine = Machine {
+            enabled: true,
+            total: 0,
+        };
+        let mut trace = Vec::new();
+        let mut position = 0;
+
+        while position < memory.len() {
+            let found = self.instructions.iter().find_map(|instruction| {
+                self.parse_at(memory, position, instruction)
+                    .map(|(args, len)| (instruction, args, len))
+            });
+
+            // malformed fragments only skip a single byte, as a valid
+            // instruction may start inside them
+            let Some((instruction, args, len)) = found else {
+                position += 1;
+                continue;
+            };
+
+            let executed = machine.enabled || !instruction.conditional;
+            if executed {
+                (instruction.handler)(&mut machine, &args);
             }
+
+            trace.push(Step {
+                position,
+                name: instruction.name,
+                args,
+                executed,
+            });
+            position += len;
         }
+
+        Execution { machine, trace }
     }
 
-    println!("Part 1: {}", p1_result);
-    println!("Part 2: {}", p2_result);
+    // parses an instruction starting at position, returning its arguments and length
+    fn parse_at(
+        &self,
+        memory: &str,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Option<(Vec<i64>, usize)> {
+        let bytes = memory.as_bytes();
+        let mut i = position;
+
+        let expect = |i: &mut usize, text: &str| {
+            let matched = bytes[*i..].starts_with(text.as_bytes());
+            *i += text.len();
+            matched
+        };
+
+        if !expect(&mut i, instruction.name) || !expect(&mut i, "(") {
+            return None;
+        }
+
+        let mut args = Vec::with_capacity(instruction.arity);
+        for n in 0..instruction.arity {
+            if n > 0 && !expect(&mut i, ",") {
+                return None;
+            }
+
+            let digits = bytes[i..].iter().take_while(|x| x.is_ascii_digit()).count();
+            if digits == 0 || digits > self.max_digits {
+                return None;
+            }
+
+            args.push(memory[i..(i + digits)].parse().ok()?);
+            i += digits;
+        }
+
+        if !expect(&mut i, ")") {
+            return None;
+        }
+
+        Some((args, i - position))
+    }
 }
 
 fn day2() {
     let reader = BufReader::new(File::open("./input/2024/day2").unwrap());
-    let reports = day2_parse(reader);
+    let reports = day2_parse(reader).unwrap();
     println!("Part 1: {}", day2p1_logic(&reports));
     println!("Part 2: {}", day2p2_logic(&reports));
 }
 
-fn day2_parse(reader: impl BufRead) -> Vec<Vec<i32>> {
-    reader
-        .lines()
-        .map(|x| {
-            // parse each whitespace separated character into an integer and collect into a vector
-            x.unwrap()
-                .split_whitespace()
-                .map(|x| x.parse::<i32>().unwrap())
-                .collect_vec()
-        })
-        // collect the vectors into a vector
-        .collect_vec()
+fn day2_parse(reader: impl BufRead) -> Result<Vec<Vec<i32>>, ParseError> {
+    let input = parse::read_input(reader)?;
+
+    // parse each whitespace separated token of each line into a vector of integers
+    parse::lines(&input).map(|line| line.tokens()).collect()
+}
+
+fn day2p1_logic(reports: &[Vec<i32>]) -> usize {
+    let rules = ReportRules::standard();
+    reports.iter().filter(|x| rules.is_safe(x)).count()
 }
 
-fn day2p1_logic(reports: &Vec<Vec<i32>>) -> i32 {
+fn day2p2_logic(reports: &[Vec<i32>]) -> usize {
+    let rules = ReportRules::standard();
     reports
         .iter()
-        .filter_map(
-            // safe reports return Some(()), unsafe reports return None
-            |report| {
-                if report.len() == 0 {
-                    return None;
-                }
-                if report.len() == 1 {
-                    return Some(());
-                }
-
-                let init_diff = report[0] - report[1];
-                // check initial safety
-                if init_diff == 0 || init_diff.abs() > 3 {
-                    return None;
-                }
-
-                let mut sliding_window = report.windows(2);
-                // skip first pair
-                sliding_window.next();
-
-                for window in sliding_window {
-                    let diff = window[0] - window[1];
-                    // check that the difference is within parameters
-                    if diff * init_diff <= 0 || diff.abs() > 3 {
-                        return None;
-                    }
-                }
-
-                // if all levels are within parameters, report safe
-                Some(())
-            },
-        )
+        .filter(|x| rules.dampen(x, 1).is_some())
         .count()
-        .try_into()
-        .unwrap()
 }
 
-fn day2p2_logic(reports: &Vec<Vec<i32>>) -> i32 {
-    reports
-        .iter()
-        .filter_map(
-            // safe reports return Some(()), unsafe reports return None
-            |report| {
-                if report.len() == 0 {
-                    return None;
-                }
-                if report.len() == 1 {
-                    return Some(());
-                }
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Monotonicity {
+    Increasing,
+    Decreasing,
+    Either,
+}
 
-                let init_diff = report[0] - report[1];
+/// What makes a report of levels safe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ReportRules {
+    // bounds on the size of each step, inclusive
+    min_step: i32,
+    max_step: i32,
+    monotonicity: Monotonicity,
+}
 
-                let mut prev = report[0];
+impl ReportRules {
+    // always increasing or always decreasing by 1 to 3
+    fn standard() -> ReportRules {
+        ReportRules {
+            min_step: 1,
+            max_step: 3,
+            monotonicity: Monotonicity::Either,
+        }
+    }
 
-                for i in 1..(report.len()) {
-                    let level = report[i];
-                    let diff = prev - level;
+    fn is_safe(&self, report: &[i32]) -> bool {
+        self.dampen(report, 0).is_some()
+    }
 
-                    if diff * init_diff <= 0 || diff.abs() > 3 {
-                        if day2_check_report(&report[..i], &report[(i + 1)..])
-                            || day2_check_report(&report[..(i - 1)], &report[i..])
-                        {
-                            return Some(());
-                        } else {
-                            return None;
-                        }
-                    }
+    /// Finds the fewest levels to remove for the report to be safe
+    ///
+    /// returns the indices of the removed levels, or None if it takes more
+    /// than `tolerance` removals. Empty reports are never safe
+    fn dampen(&self, report: &[i32], tolerance: usize) -> Option<Vec<usize>> {
+        let directions: &[bool] = match self.monotonicity {
+            Monotonicity::Increasing => &[true],
+            Monotonicity::Decreasing => &[false],
+            Monotonicity::Either => &[true, false],
+        };
 
-                    prev = level;
-                }
+        directions
+            .iter()
+            .filter_map(|increasing| self.dampen_direction(report, tolerance, *increasing))
+            .min_by_key(|x| x.len())
+    }
 
-                // if all levels are within parameters, report safe
-                Some(())
-            },
-        )
-        .count()
-        .try_into()
-        .unwrap()
-}
+    /// Dynamic programming over which level was kept last, O(n * tolerance)
+    ///
+    /// removing every level between two kept levels costs the gap between
+    /// them, so only the previous tolerance + 1 levels can come before one
+    fn dampen_direction(
+        &self,
+        report: &[i32],
+        tolerance: usize,
+        increasing: bool,
+    ) -> Option<Vec<usize>> {
+        let valid = |from: i32, to: i32| {
+            let step = if increasing { to - from } else { from - to };
+            self.min_step <= step && step <= self.max_step
+        };
 
-fn day2_check_report(report1: &[i32], report2: &[i32]) -> bool {
-    let report = [report1, report2].concat();
+        // fewest removals keeping level i as the last kept level so far
+        let mut removals: Vec<usize> = Vec::with_capacity(report.len());
+        let mut previous: Vec<Option<usize>> = Vec::with_capacity(report.len());
 
-    let mut prev = report[0];
-    let init_diff = report[0] - report[1];
+        for (i, level) in report.iter().enumerate() {
+            // remove everything before this level
+            let mut best = (i, None);
 
-    for level in report[1..].iter() {
-        let diff = prev - level;
+            for j in (i.saturating_sub(tolerance + 1)..i).rev() {
+                let cost = removals[j] + (i - j - 1);
+                if cost < best.0 && valid(report[j], *level) {
+                    best = (cost, Some(j));
+                }
+            }
 
-        if diff * init_diff <= 0 || diff.abs() > 3 {
-            return false;
+            removals.push(best.0);
+            previous.push(best.1);
         }
 
-        prev = *level;
-    }
+        // remove everything after the last kept level
+        let (last, _) = removals
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (i, x + report.len() - 1 - i))
+            .filter(|(_, x)| *x <= tolerance)
+            .min_by_key(|(_, x)| *x)?;
+
+        let mut kept = vec![false; report.len()];
+        let mut current = Some(last);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = previous[i];
+        }
 
-    true
+        Some((0..report.len()).filter(|i| !kept[*i]).collect())
+    }
 }
 
 fn day1() {
     let reader = BufReader::new(File::open("./input/2024/day1").unwrap());
-    let (mut list1, mut list2) = day1_parse(reader);
+    let (mut list1, mut list2) = day1_parse(reader).unwrap();
     println!("Part 1: {}", day1p1_logic(&mut list1, &mut list2));
     println!("Part 2: {}", day1p2_logic(&list1, &list2));
 }
 
-fn day1_parse(reader: impl BufRead) -> (Vec<i32>, Vec<i32>) {
-    reader
-        .lines()
-        .map(|x| {
-            x.unwrap()
-                .split_whitespace()
-                .map(|x| x.parse::<i32>().unwrap())
-                .next_tuple::<(i32, i32)>()
-                .unwrap()
+fn day1_parse(reader: impl BufRead) -> Result<(Vec<i32>, Vec<i32>), ParseError> {
+    let input = parse::read_input(reader)?;
+
+    parse::lines(&input)
+        .map(|line| match line.tokens::<i32>()?[..] {
+            [left, right] => Ok((left, right)),
+            _ => Err(line.error_at(0, "expected two numbers")),
         })
-        .unzip()
+        .collect()
 }
 
 fn day1p1_logic(list1: &mut Vec<i32>, list2: &mut Vec<i32>) -> i32 {
@@ -665,9 +710,12 @@ fn day1p2_logic(list1: &Vec<i32>, list2: &Vec<i32>) -> i32 {
 
 #[cfg(test)]
 mod test {
-    use crate::years::year2024::{day2p1_logic, day2p2_logic};
+    use crate::years::year2024::{
+        day2p1_logic, day2p2_logic, day3_scanner, day4p1_logic, day4p2_logic, day5p1_logic,
+        day5p2_logic, day6p1_logic, day6p2_logic,
+    };
 
-    use super::day2_parse;
+    use super::{day2_parse, day4_parse, day5_parse, day6_parse, Monotonicity, ReportRules};
 
     #[test]
     fn day2p1() {
@@ -678,8 +726,169 @@ mod test {
 8 6 4 4 1
 1 3 6 7 9";
 
-        let reports = day2_parse(input.as_bytes());
+        let reports = day2_parse(input.as_bytes()).unwrap();
         assert_eq!(2, day2p1_logic(&reports));
         assert_eq!(4, day2p2_logic(&reports));
     }
+
+    #[test]
+    fn day2_dampener() {
+        let rules = ReportRules::standard();
+
+        assert_eq!(Some(vec![]), rules.dampen(&[7, 6, 4, 2, 1], 1));
+        assert_eq!(Some(vec![1]), rules.dampen(&[1, 3, 2, 4, 5], 1));
+        assert_eq!(Some(vec![2]), rules.dampen(&[8, 6, 4, 4, 1], 1));
+        assert_eq!(None, rules.dampen(&[1, 9, 2, 8, 3], 1));
+        assert_eq!(Some(vec![1, 3]), rules.dampen(&[1, 9, 2, 8, 3], 2));
+        // dropping the first level is enough to change direction
+        assert_eq!(Some(vec![0]), rules.dampen(&[5, 1, 2, 3], 1));
+        assert_eq!(Some(vec![1, 2]), rules.dampen(&[1, 20, 30, 4], 3));
+        assert_eq!(None, rules.dampen(&[], 3));
+
+        let stairs = ReportRules {
+            min_step: 1,
+            max_step: 1,
+            monotonicity: Monotonicity::Increasing,
+        };
+        assert!(stairs.is_safe(&[1, 2, 3]));
+        assert!(!stairs.is_safe(&[3, 2, 1]));
+        assert_eq!(Some(vec![2, 3]), stairs.dampen(&[1, 2, 9, 3, 3, 4], 2));
+    }
+
+    #[test]
+    fn day3() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+        assert_eq!(161, day3_scanner(false).run(input).machine.total);
+
+        let execution = day3_scanner(true).run(input);
+        assert_eq!(48, execution.machine.total);
+        assert_eq!(
+            vec![
+                ("mul", true),
+                ("don't", true),
+                ("mul", false),
+                ("mul", false),
+                ("do", true),
+                ("mul", true)
+            ],
+            execution
+                .trace
+                .iter()
+                .map(|x| (x.name, x.executed))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![8, 5], execution.trace[5].args);
+    }
+
+    #[test]
+    fn day3_malformed_and_custom() {
+        // an unfinished instruction doesn't hide the one inside it
+        let mut scanner = day3_scanner(true);
+        assert_eq!(
+            6,
+            scanner.run("mul(mul(2,3)mul(1234,5)mul(1,)").machine.total
+        );
+
+        scanner.register("add", 3, true, |machine, args| {
+            machine.total += args.iter().sum::<i64>()
+        });
+        let execution = scanner.run("add(1,2,3)don't()add(4,5,6)do()mul(2,2)add(1,2)");
+        assert_eq!(10, execution.machine.total);
+        assert_eq!(5, execution.trace.len());
+        assert_eq!(31, execution.trace[4].position);
+    }
+
+    #[test]
+    fn day4() {
+        let input = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+        let search = day4_parse(input.as_bytes()).unwrap();
+        assert_eq!(18, day4p1_logic(&search));
+        assert_eq!(9, day4p2_logic(&search));
+    }
+
+    #[test]
+    fn day5() {
+        let input = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+        let (rules, updates) = day5_parse(input.as_bytes()).unwrap();
+        assert_eq!(143, day5p1_logic(&rules, &updates));
+        assert_eq!(Ok(123), day5p2_logic(&rules, &updates));
+    }
+
+    #[test]
+    fn day6() {
+        let input = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+        let patrol = day6_parse(input.as_bytes()).unwrap();
+        assert_eq!(41, day6p1_logic(&patrol));
+        assert_eq!(6, day6p2_logic(&patrol));
+
+        let mut positions = patrol.loop_positions();
+        positions.sort();
+        assert_eq!(
+            vec![(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)],
+            positions
+        );
+    }
+
+    #[test]
+    fn day6_obstacle_off_the_jump_table() {
+        // the guard would walk off the map on the way back past their start,
+        // blocking that sends them around the same turns again
+        let input = ".#...
+....#
+.^...
+...#.";
+
+        let patrol = day6_parse(input.as_bytes()).unwrap();
+        assert_eq!(vec![(2, 0)], patrol.loop_positions());
+        assert!(day6_parse("..\n^^".as_bytes()).is_err());
+    }
 }